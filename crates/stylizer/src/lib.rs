@@ -1,9 +1,13 @@
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
 use wasm_bindgen_futures::JsFuture;
 use serde::{Deserialize, Serialize};
-use js_sys::{Uint8Array, Promise};
-use web_sys::{window, Request, RequestInit, RequestMode, Response};
+use js_sys::{Array, Uint8Array, Promise};
+use web_sys::{window, DedicatedWorkerGlobalScope, MessageEvent, Request, RequestInit, RequestMode, Response, Worker};
 use once_cell::unsync::OnceCell;
+use std::cell::RefCell;
+use std::rc::Rc;
 use thiserror::Error;
 
 // WONNX
@@ -93,8 +97,19 @@ pub async fn load_model(meta_json: String) -> Result<(), JsValue> {
     Ok(())
 }
 
-fn bilinear_resize_rgba(src: &[u8], sw: u32, sh: u32, dw: u32, dh: u32) -> Vec<u8> {
+/// Bilinear resize that interpolates in premultiplied-alpha space: straight RGBA
+/// interpolation bleeds a fully-transparent pixel's (often black) RGB into its
+/// opaque neighbors, producing dark halos on cutouts/logos. Premultiplying first,
+/// interpolating, then un-premultiplying avoids that (RGB is emitted as 0 where
+/// the interpolated alpha is 0, to dodge a divide-by-zero).
+pub fn bilinear_resize_rgba(src: &[u8], sw: u32, sh: u32, dw: u32, dh: u32) -> Vec<u8> {
     if sw == dw && sh == dh { return src.to_vec(); }
+
+    let premultiplied: Vec<f32> = src.chunks_exact(4).flat_map(|p| {
+        let a = p[3] as f32 / 255.0;
+        [p[0] as f32 * a, p[1] as f32 * a, p[2] as f32 * a, p[3] as f32]
+    }).collect();
+
     let mut dst = vec![0u8; (dw * dh * 4) as usize];
     let x_ratio = (sw - 1) as f32 / dw as f32;
     let y_ratio = (sh - 1) as f32 / dh as f32;
@@ -109,22 +124,31 @@ fn bilinear_resize_rgba(src: &[u8], sw: u32, sh: u32, dw: u32, dh: u32) -> Vec<u
             let x_h = (x_l + 1).min(sw - 1);
             let x_w = fx - x_l as f32;
             let idx = ((y * dw + x) * 4) as usize;
-            for c in 0..4 {
-                let p00 = src[((y_l * sw + x_l) * 4 + c) as usize] as f32;
-                let p10 = src[((y_l * sw + x_h) * 4 + c) as usize] as f32;
-                let p01 = src[((y_h * sw + x_l) * 4 + c) as usize] as f32;
-                let p11 = src[((y_h * sw + x_h) * 4 + c) as usize] as f32;
+
+            let mut channels = [0f32; 4];
+            for c in 0..4usize {
+                let p00 = premultiplied[((y_l * sw + x_l) * 4) as usize + c];
+                let p10 = premultiplied[((y_l * sw + x_h) * 4) as usize + c];
+                let p01 = premultiplied[((y_h * sw + x_l) * 4) as usize + c];
+                let p11 = premultiplied[((y_h * sw + x_h) * 4) as usize + c];
                 let p0 = p00 * (1.0 - x_w) + p10 * x_w;
                 let p1 = p01 * (1.0 - x_w) + p11 * x_w;
-                let p = p0 * (1.0 - y_w) + p1 * y_w;
-                dst[idx + c] = p.round().clamp(0.0, 255.0) as u8;
+                channels[c] = p0 * (1.0 - y_w) + p1 * y_w;
             }
+
+            let alpha_frac = channels[3] / 255.0;
+            if alpha_frac > 0.0 {
+                dst[idx] = (channels[0] / alpha_frac).round().clamp(0.0, 255.0) as u8;
+                dst[idx + 1] = (channels[1] / alpha_frac).round().clamp(0.0, 255.0) as u8;
+                dst[idx + 2] = (channels[2] / alpha_frac).round().clamp(0.0, 255.0) as u8;
+            }
+            dst[idx + 3] = channels[3].round().clamp(0.0, 255.0) as u8;
         }
     }
     dst
 }
 
-fn nchw_from_rgba(
+pub fn nchw_from_rgba(
     rgba: &[u8], w: u32, h: u32, mean: [f32; 3], std: [f32; 3], scale: f32
 ) -> Vec<f32> {
     // output: [1,3,h,w]
@@ -150,7 +174,11 @@ fn nchw_from_rgba(
     out
 }
 
-fn rgba_from_nchw(data: &[f32], w: u32, h: u32, mean: [f32;3], std: [f32;3], scale: f32) -> Vec<u8> {
+/// Converts a model's `[1,3,h,w]` output back to RGBA. The tensor itself carries no
+/// alpha plane, so `source_rgba` — the (already resized-to-`w`x`h`) RGBA the
+/// tensor was produced from — supplies the alpha to carry through unchanged,
+/// instead of hardcoding opaque.
+pub fn rgba_from_nchw(data: &[f32], w: u32, h: u32, mean: [f32;3], std: [f32;3], scale: f32, source_rgba: &[u8]) -> Vec<u8> {
     let plane = (w * h) as usize;
     let mut out = vec![0u8; (w * h * 4) as usize];
     for y in 0..h as usize {
@@ -167,7 +195,7 @@ fn rgba_from_nchw(data: &[f32], w: u32, h: u32, mean: [f32;3], std: [f32;3], sca
             out[i]     = (r * 255.0).clamp(0.0, 255.0) as u8;
             out[i + 1] = (g * 255.0).clamp(0.0, 255.0) as u8;
             out[i + 2] = (b * 255.0).clamp(0.0, 255.0) as u8;
-            out[i + 3] = 255u8;
+            out[i + 3] = source_rgba[i + 3];
         }
     }
     out
@@ -203,24 +231,403 @@ pub async fn run_style(
         .ok_or_else(|| StylizerError::Web("missing output".into()))?;
 
     // WONNX returns owned f32 vec for the output
-    let stylized = rgba_from_nchw(&out, meta.input_width, meta.input_height, meta.mean, meta.std, meta.scale);
+    let stylized = rgba_from_nchw(&out, meta.input_width, meta.input_height, meta.mean, meta.std, meta.scale, &resized);
 
     // Return (model resolution). Frontend can scale back to original size & blend.
     Ok(Uint8Array::from(stylized.as_slice()))
 }
 
-// Optional helper: blend in WASM if you prefer
+/// Reflect an out-of-range source coordinate back into `[0, n)` (a mirror that
+/// repeats the edge pixel: `-1` maps to `0`, `n` to `n - 1`) so border tiles can
+/// be padded past the image bounds.
+fn reflect(i: i64, n: u32) -> u32 {
+    let n = n as i64;
+    let mut idx = i;
+    while idx < 0 || idx >= n {
+        if idx < 0 {
+            idx = -idx - 1;
+        } else {
+            idx = 2 * n - idx - 1;
+        }
+    }
+    idx as u32
+}
+
+/// Crop a `w`x`h` RGBA tile anchored at `(x, y)` in `data` (a `full_w`x`full_h`
+/// image), reflection-padding any part of the tile that falls outside the image.
+fn crop_rgba_reflect(data: &[u8], full_w: u32, full_h: u32, x: i64, y: i64, w: u32, h: u32) -> Vec<u8> {
+    let mut out = vec![0u8; (w * h * 4) as usize];
+    for ty in 0..h {
+        let sy = reflect(y + ty as i64, full_h);
+        for tx in 0..w {
+            let sx = reflect(x + tx as i64, full_w);
+            let src_idx = ((sy * full_w + sx) * 4) as usize;
+            let dst_idx = ((ty * w + tx) * 4) as usize;
+            out[dst_idx..dst_idx + 4].copy_from_slice(&data[src_idx..src_idx + 4]);
+        }
+    }
+    out
+}
+
+/// Raised-cosine feather weight: 1.0 in the tile interior, ramping to 0 over
+/// `overlap` pixels at both ends of `extent`. `run_style_tiled` keeps an edge
+/// that lies on the image border at full weight by shifting `coord` and
+/// widening `extent` by `overlap` on that side.
+///
+/// `rust-core`'s `StyleTransferEngine::feather_weight` is the same curve for
+/// the same reason. `crates/stylizer` and `rust-core` are independent crates
+/// in this snapshot with no dependency edge between them, so there's nowhere
+/// to hang a shared helper without first introducing one -- hence the
+/// duplication, not because of any missing workspace manifest specifically.
+fn feather_weight(coord: u32, extent: u32, overlap: u32) -> f32 {
+    if overlap == 0 {
+        return 1.0;
+    }
+    let leading = if coord < overlap {
+        0.5 * (1.0 - (std::f32::consts::PI * coord as f32 / overlap as f32).cos())
+    } else {
+        1.0
+    };
+    let dist_from_end = extent.saturating_sub(coord + 1);
+    let trailing = if dist_from_end < overlap {
+        0.5 * (1.0 - (std::f32::consts::PI * dist_from_end as f32 / overlap as f32).cos())
+    } else {
+        1.0
+    };
+    leading.min(trailing)
+}
+
+/// Tiled variant of `run_style` for full-resolution images: splits `input_rgba`
+/// into overlapping `meta.input_width`x`input_height` patches (reflection-padded
+/// at the borders), stylizes each independently, and recombines them with a
+/// separable feathered blend so tile seams disappear. Falls back to `run_style`
+/// directly when the source already fits in one tile.
+#[wasm_bindgen]
+pub async fn run_style_tiled(
+    input_rgba: Uint8Array,
+    in_width: u32,
+    in_height: u32,
+    overlap: u32,
+) -> Result<Uint8Array, JsValue> {
+    let tile_w;
+    let tile_h;
+    {
+        let dims = STATE.with(|s| {
+            s.get().map(|st| (st.meta.input_width, st.meta.input_height)).ok_or(StylizerError::ModelNotLoaded)
+        }).map_err(JsValue::from)?;
+        tile_w = dims.0;
+        tile_h = dims.1;
+    }
+
+    if overlap >= tile_w.min(tile_h) {
+        return Err(StylizerError::InvalidDims.into());
+    }
+
+    if in_width <= tile_w && in_height <= tile_h {
+        return run_style(input_rgba, in_width, in_height).await;
+    }
+
+    let rgba = input_rgba.to_vec();
+    let stride_x = tile_w - overlap;
+    let stride_y = tile_h - overlap;
+
+    let mut accum = vec![0f32; (in_width * in_height * 3) as usize];
+    let mut weight_sum = vec![0f32; (in_width * in_height) as usize];
+
+    let mut ty = 0i64;
+    loop {
+        let mut tx = 0i64;
+        loop {
+            let (meta, session) = STATE.with(|s| {
+                let st = s.get();
+                st.map(|st| (st.meta.clone(), &st.session)).ok_or(StylizerError::ModelNotLoaded)
+            }).map_err(JsValue::from)?;
+
+            let tile_rgba = crop_rgba_reflect(&rgba, in_width, in_height, tx, ty, tile_w, tile_h);
+            let input_tensor = nchw_from_rgba(&tile_rgba, tile_w, tile_h, meta.mean, meta.std, meta.scale);
+
+            let mut outputs = session
+                .run(vec![(meta.input_name.clone(), input_tensor)])
+                .await
+                .map_err(|e| StylizerError::Web(format!("wonnx run: {e}")))?;
+            let out = outputs
+                .remove(&meta.output_name)
+                .ok_or_else(|| StylizerError::Web("missing output".into()))?;
+            let stylized_tile = rgba_from_nchw(&out, tile_w, tile_h, meta.mean, meta.std, meta.scale, &tile_rgba);
+
+            // Only ramp toward edges shared with another tile; edges on the image
+            // border keep full weight, since no other tile covers those pixels.
+            let lead_x = if tx == 0 { overlap } else { 0 };
+            let trail_x = if tx + tile_w as i64 >= in_width as i64 { overlap } else { 0 };
+            let lead_y = if ty == 0 { overlap } else { 0 };
+            let trail_y = if ty + tile_h as i64 >= in_height as i64 { overlap } else { 0 };
+
+            for py in 0..tile_h {
+                let gy = ty + py as i64;
+                if gy < 0 || gy >= in_height as i64 {
+                    continue;
+                }
+                for px in 0..tile_w {
+                    let gx = tx + px as i64;
+                    if gx < 0 || gx >= in_width as i64 {
+                        continue;
+                    }
+
+                    let weight = feather_weight(px + lead_x, tile_w + lead_x + trail_x, overlap)
+                        * feather_weight(py + lead_y, tile_h + lead_y + trail_y, overlap);
+                    let src_idx = ((py * tile_w + px) * 4) as usize;
+                    let dst_pixel = (gy as u32 * in_width + gx as u32) as usize;
+
+                    accum[dst_pixel * 3] += stylized_tile[src_idx] as f32 * weight;
+                    accum[dst_pixel * 3 + 1] += stylized_tile[src_idx + 1] as f32 * weight;
+                    accum[dst_pixel * 3 + 2] += stylized_tile[src_idx + 2] as f32 * weight;
+                    weight_sum[dst_pixel] += weight;
+                }
+            }
+
+            if tx + tile_w as i64 >= in_width as i64 {
+                break;
+            }
+            tx += stride_x as i64;
+        }
+
+        if ty + tile_h as i64 >= in_height as i64 {
+            break;
+        }
+        ty += stride_y as i64;
+    }
+
+    let mut result = vec![0u8; (in_width * in_height * 4) as usize];
+    for pixel in 0..(in_width * in_height) as usize {
+        let w = weight_sum[pixel].max(f32::EPSILON);
+        result[pixel * 4] = (accum[pixel * 3] / w).round().clamp(0.0, 255.0) as u8;
+        result[pixel * 4 + 1] = (accum[pixel * 3 + 1] / w).round().clamp(0.0, 255.0) as u8;
+        result[pixel * 4 + 2] = (accum[pixel * 3 + 2] / w).round().clamp(0.0, 255.0) as u8;
+        result[pixel * 4 + 3] = 255u8;
+    }
+
+    Ok(Uint8Array::from(result.as_slice()))
+}
+
+/// Optional helper: blend in WASM if you prefer. Source-over composites `top`
+/// onto `base` (top's alpha scaled by `strength`), carrying the resulting alpha
+/// through instead of forcing opaque — so the blend stays usable when either
+/// layer has real transparency.
 #[wasm_bindgen]
 pub fn blend_rgba(base_rgba: Uint8Array, top_rgba: Uint8Array, width: u32, height: u32, strength: f32) -> Result<Uint8Array, JsValue> {
     let mut base = base_rgba.to_vec();
     let top = top_rgba.to_vec();
     if base.len() != top.len() { return Err(StylizerError::InvalidDims.into()); }
-    let a = strength.clamp(0.0, 1.0);
+    let strength = strength.clamp(0.0, 1.0);
     for i in (0..base.len()).step_by(4) {
-        base[i]     = ((1.0 - a) * base[i]     as f32 + a * top[i]     as f32).round() as u8;
-        base[i + 1] = ((1.0 - a) * base[i + 1] as f32 + a * top[i + 1] as f32).round() as u8;
-        base[i + 2] = ((1.0 - a) * base[i + 2] as f32 + a * top[i + 2] as f32).round() as u8;
-        base[i + 3] = 255u8;
+        let base_a = base[i + 3] as f32 / 255.0;
+        let top_a = (top[i + 3] as f32 / 255.0) * strength;
+        let out_a = top_a + base_a * (1.0 - top_a);
+
+        if out_a > 0.0 {
+            for c in 0..3 {
+                let composited = top[i + c] as f32 * top_a + base[i + c] as f32 * base_a * (1.0 - top_a);
+                base[i + c] = (composited / out_a).round().clamp(0.0, 255.0) as u8;
+            }
+        } else {
+            base[i] = 0;
+            base[i + 1] = 0;
+            base[i + 2] = 0;
+        }
+        base[i + 3] = (out_a * 255.0).round().clamp(0.0, 255.0) as u8;
     }
     Ok(Uint8Array::from(base.as_slice()))
 }
+
+// --- Worker-backed execution -------------------------------------------------
+//
+// A single WONNX pass blocks whichever thread runs it for hundreds of
+// milliseconds, which is unacceptable on the main/render thread. The types and
+// functions below let `load_model`/`run_style`/`blend_rgba` run inside a
+// dedicated Web Worker instead: `install_worker_handler` services requests on
+// the worker side (where `STATE`/the WONNX `Session` stay resident for the
+// worker's lifetime), and `StylizerClient` is a thin main-thread handle that
+// posts requests and resolves a `Promise` when the worker replies.
+
+/// A request posted from the main thread to the inference worker. `RunStyle`
+/// and `Blend` carry their RGBA buffers alongside the message (as transferables
+/// in the `postMessage` call, not inline in this enum) to avoid copying them.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum WorkerRequest {
+    LoadModel { meta: ModelMeta },
+    RunStyle { width: u32, height: u32 },
+    Blend { width: u32, height: u32, strength: f32 },
+}
+
+/// The worker's reply to a `WorkerRequest`. `RunStyle`/`Blend` successes carry
+/// their output buffer as a transferable alongside the message, not inline here.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum WorkerResponse {
+    Loaded,
+    Styled,
+    Blended,
+    Error { message: String },
+}
+
+fn post_worker_response(scope: &DedicatedWorkerGlobalScope, response: &WorkerResponse, buffer: Option<Uint8Array>) {
+    let json = serde_json::to_string(response).unwrap_or_else(|e| {
+        serde_json::to_string(&WorkerResponse::Error { message: e.to_string() }).unwrap()
+    });
+    let payload = Array::of1(&JsValue::from_str(&json));
+    let transfer = Array::new();
+    if let Some(buf) = buffer {
+        payload.push(&buf);
+        transfer.push(&buf.buffer());
+    }
+    let _ = scope.post_message_with_transfer(&payload, &transfer);
+}
+
+/// Installs the `onmessage` handler that services `WorkerRequest`s inside a
+/// dedicated worker. Call this once from the worker script after the wasm
+/// module has initialized — `STATE` then stays resident on this thread for as
+/// long as the worker lives.
+#[wasm_bindgen]
+pub fn install_worker_handler() -> Result<(), JsValue> {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+
+    let handler_scope = scope.clone();
+    let onmessage = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+        let scope = handler_scope.clone();
+        let payload: Array = event.data().unchecked_into();
+        let request_json = payload.get(0).as_string().unwrap_or_default();
+        let buffer_a: Option<Uint8Array> = payload.get(1).dyn_into().ok();
+        let buffer_b: Option<Uint8Array> = payload.get(2).dyn_into().ok();
+
+        wasm_bindgen_futures::spawn_local(async move {
+            let request: WorkerRequest = match serde_json::from_str(&request_json) {
+                Ok(r) => r,
+                Err(e) => {
+                    post_worker_response(&scope, &WorkerResponse::Error { message: e.to_string() }, None);
+                    return;
+                }
+            };
+
+            match request {
+                WorkerRequest::LoadModel { meta } => {
+                    let meta_json = serde_json::to_string(&meta).unwrap();
+                    match load_model(meta_json).await {
+                        Ok(()) => post_worker_response(&scope, &WorkerResponse::Loaded, None),
+                        Err(e) => post_worker_response(&scope, &WorkerResponse::Error { message: format!("{:?}", e) }, None),
+                    }
+                }
+                WorkerRequest::RunStyle { width, height } => {
+                    let Some(input) = buffer_a else {
+                        post_worker_response(&scope, &WorkerResponse::Error { message: "missing input buffer".into() }, None);
+                        return;
+                    };
+                    match run_style(input, width, height).await {
+                        Ok(out) => post_worker_response(&scope, &WorkerResponse::Styled, Some(out)),
+                        Err(e) => post_worker_response(&scope, &WorkerResponse::Error { message: format!("{:?}", e) }, None),
+                    }
+                }
+                WorkerRequest::Blend { width, height, strength } => {
+                    let (Some(base), Some(top)) = (buffer_a, buffer_b) else {
+                        post_worker_response(&scope, &WorkerResponse::Error { message: "missing base/top buffer".into() }, None);
+                        return;
+                    };
+                    match blend_rgba(base, top, width, height, strength) {
+                        Ok(out) => post_worker_response(&scope, &WorkerResponse::Blended, Some(out)),
+                        Err(e) => post_worker_response(&scope, &WorkerResponse::Error { message: format!("{:?}", e) }, None),
+                    }
+                }
+            }
+        });
+    });
+
+    scope.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
+    Ok(())
+}
+
+/// Main-thread handle to a dedicated inference worker. Mirrors the async
+/// signatures of `load_model`/`run_style`/`blend_rgba`, but the heavy WONNX work
+/// runs off the render thread; each method resolves once the worker replies.
+/// Only one request may be in flight at a time (matches the single resident
+/// `Session` the worker services requests against).
+#[wasm_bindgen]
+pub struct StylizerClient {
+    worker: Worker,
+    pending: Rc<RefCell<Option<js_sys::Function>>>,
+}
+
+#[wasm_bindgen]
+impl StylizerClient {
+    #[wasm_bindgen(constructor)]
+    pub fn new(worker_script_url: &str) -> Result<StylizerClient, JsValue> {
+        let worker = Worker::new(worker_script_url)?;
+        let pending = Rc::new(RefCell::new(None));
+
+        let pending_for_closure = pending.clone();
+        let onmessage = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+            if let Some(resolve) = pending_for_closure.borrow_mut().take() {
+                let _ = resolve.call1(&JsValue::NULL, &event.data());
+            }
+        });
+        worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+        onmessage.forget();
+
+        Ok(StylizerClient { worker, pending })
+    }
+
+    fn post(&self, request: &WorkerRequest, buffers: &[&Uint8Array]) -> Result<Promise, JsValue> {
+        let json = serde_json::to_string(request).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let payload = Array::of1(&JsValue::from_str(&json));
+        let transfer = Array::new();
+        for buf in buffers {
+            payload.push(buf);
+            transfer.push(&buf.buffer());
+        }
+
+        let worker = self.worker.clone();
+        let pending = self.pending.clone();
+        Ok(Promise::new(&mut move |resolve, _reject| {
+            *pending.borrow_mut() = Some(resolve);
+            let _ = worker.post_message_with_transfer(&payload, &transfer);
+        }))
+    }
+
+    fn parse_reply(data: JsValue) -> Result<(WorkerResponse, Option<Uint8Array>), JsValue> {
+        let payload: Array = data.dyn_into()?;
+        let json = payload.get(0).as_string().ok_or_else(|| JsValue::from_str("malformed worker reply"))?;
+        let response: WorkerResponse = serde_json::from_str(&json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let buffer: Option<Uint8Array> = payload.get(1).dyn_into().ok();
+        Ok((response, buffer))
+    }
+
+    pub async fn load_model(&self, meta_json: String) -> Result<(), JsValue> {
+        let meta: ModelMeta = serde_json::from_str(&meta_json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let reply = JsFuture::from(self.post(&WorkerRequest::LoadModel { meta }, &[])?).await?;
+        match Self::parse_reply(reply)? {
+            (WorkerResponse::Loaded, _) => Ok(()),
+            (WorkerResponse::Error { message }, _) => Err(JsValue::from_str(&message)),
+            _ => Err(JsValue::from_str("unexpected worker reply")),
+        }
+    }
+
+    pub async fn run_style(&self, input_rgba: Uint8Array, width: u32, height: u32) -> Result<Uint8Array, JsValue> {
+        let request = WorkerRequest::RunStyle { width, height };
+        let reply = JsFuture::from(self.post(&request, &[&input_rgba])?).await?;
+        match Self::parse_reply(reply)? {
+            (WorkerResponse::Styled, Some(buf)) => Ok(buf),
+            (WorkerResponse::Error { message }, _) => Err(JsValue::from_str(&message)),
+            _ => Err(JsValue::from_str("unexpected worker reply")),
+        }
+    }
+
+    pub async fn blend(&self, base_rgba: Uint8Array, top_rgba: Uint8Array, width: u32, height: u32, strength: f32) -> Result<Uint8Array, JsValue> {
+        let request = WorkerRequest::Blend { width, height, strength };
+        let reply = JsFuture::from(self.post(&request, &[&base_rgba, &top_rgba])?).await?;
+        match Self::parse_reply(reply)? {
+            (WorkerResponse::Blended, Some(buf)) => Ok(buf),
+            (WorkerResponse::Error { message }, _) => Err(JsValue::from_str(&message)),
+            _ => Err(JsValue::from_str("unexpected worker reply")),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::webgpu_backend::WebGPUBackend;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+/// The execution providers `BackendSelector` knows how to probe, in priority order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExecutionProvider {
+    WebGpu,
+    WebGl,
+    WasmSimd,
+}
+
+impl ExecutionProvider {
+    fn label(&self) -> &'static str {
+        match self {
+            ExecutionProvider::WebGpu => "WebGPU",
+            ExecutionProvider::WebGl => "WebGL",
+            ExecutionProvider::WasmSimd => "WASM-SIMD CPU",
+        }
+    }
+}
+
+/// Why a candidate provider was rejected, so `get_backend_report` can explain the
+/// choice instead of the failure disappearing into `console_log!`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderOutcome {
+    pub provider: String,
+    pub accepted: bool,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendReport {
+    pub chosen: Option<String>,
+    pub candidates: Vec<ProviderOutcome>,
+}
+
+/// Required capabilities a model expects from whichever execution provider runs it.
+#[derive(Debug, Clone, Copy)]
+pub struct ModelRequirements {
+    pub min_input_size: u32,
+    pub min_memory_bytes: u64,
+}
+
+/// Probes `WebGpu -> WebGl -> WasmSimd` in order, keeping the adapter/limits obtained
+/// from `WebGPUBackend` and the rejection reason for every provider that didn't make
+/// the cut, so failures stay inspectable via `get_backend_report` instead of being
+/// swallowed by a single `if`.
+pub struct BackendSelector {
+    webgpu: WebGPUBackend,
+    report: BackendReport,
+}
+
+impl BackendSelector {
+    pub fn new() -> Self {
+        Self {
+            webgpu: WebGPUBackend::new(),
+            report: BackendReport {
+                chosen: None,
+                candidates: Vec::new(),
+            },
+        }
+    }
+
+    /// Probe providers in order and select the first one that both initializes and
+    /// satisfies `requirements`. Returns the chosen provider.
+    pub async fn select(&mut self, requirements: ModelRequirements) -> ExecutionProvider {
+        self.report.candidates.clear();
+
+        if let Some(chosen) = self.try_webgpu(requirements).await {
+            self.report.chosen = Some(chosen.label().to_string());
+            return chosen;
+        }
+
+        if let Some(chosen) = self.try_webgl(requirements) {
+            self.report.chosen = Some(chosen.label().to_string());
+            return chosen;
+        }
+
+        self.report.candidates.push(ProviderOutcome {
+            provider: ExecutionProvider::WasmSimd.label().to_string(),
+            accepted: true,
+            reason: "always available as the final fallback".to_string(),
+        });
+        self.report.chosen = Some(ExecutionProvider::WasmSimd.label().to_string());
+        ExecutionProvider::WasmSimd
+    }
+
+    async fn try_webgpu(&mut self, requirements: ModelRequirements) -> Option<ExecutionProvider> {
+        match self.webgpu.initialize().await {
+            Ok(()) => {
+                let limits_ok = self.webgpu.max_texture_dimension() >= requirements.min_input_size
+                    && self.webgpu.max_buffer_size() >= requirements.min_memory_bytes;
+
+                if limits_ok {
+                    self.report.candidates.push(ProviderOutcome {
+                        provider: ExecutionProvider::WebGpu.label().to_string(),
+                        accepted: true,
+                        reason: "adapter acquired and within required limits".to_string(),
+                    });
+                    Some(ExecutionProvider::WebGpu)
+                } else {
+                    self.report.candidates.push(ProviderOutcome {
+                        provider: ExecutionProvider::WebGpu.label().to_string(),
+                        accepted: false,
+                        reason: format!(
+                            "adapter limits too small (max_texture_dimension={}, max_buffer_size={})",
+                            self.webgpu.max_texture_dimension(),
+                            self.webgpu.max_buffer_size()
+                        ),
+                    });
+                    None
+                }
+            }
+            Err(e) => {
+                let reason = e.as_string().unwrap_or_else(|| "WebGPU adapter request failed".to_string());
+                self.report.candidates.push(ProviderOutcome {
+                    provider: ExecutionProvider::WebGpu.label().to_string(),
+                    accepted: false,
+                    reason,
+                });
+                None
+            }
+        }
+    }
+
+    fn try_webgl(&mut self, _requirements: ModelRequirements) -> Option<ExecutionProvider> {
+        // No WebGL2 compute path is wired up yet; record it as rejected so the report
+        // is honest about why WASM-SIMD was reached.
+        self.report.candidates.push(ProviderOutcome {
+            provider: ExecutionProvider::WebGl.label().to_string(),
+            accepted: false,
+            reason: "WebGL execution provider not implemented".to_string(),
+        });
+        None
+    }
+
+    pub fn webgpu_backend(&self) -> &WebGPUBackend {
+        &self.webgpu
+    }
+
+    /// JS-facing report of which backend was chosen and why the others were
+    /// rejected: `chosen` holds the selected provider's label (e.g. "WebGPU" or
+    /// "WASM-SIMD CPU") and `candidates` lists every probed provider with
+    /// `accepted` and a `reason` string.
+    pub fn get_backend_report(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.report)
+            .map_err(|e| JsValue::from_str(&format!("Serialization failed: {}", e)))
+    }
+}
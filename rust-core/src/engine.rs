@@ -1,6 +1,12 @@
 use wasm_bindgen::prelude::*;
 use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
+use wonnx::session::Session as WonnxSession;
+use tract_onnx::prelude::*;
+
+/// A runnable CPU model built from an ONNX `ModelProto` by tract, used as the
+/// `Backend::Cpu` fallback when no WebGPU device is available.
+type TractModel = SimplePlan<TypedFact, Box<dyn TypedOp>, Graph<TypedFact, Box<dyn TypedOp>>>;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ModelMetadata {
@@ -10,6 +16,41 @@ pub struct ModelMetadata {
     pub output_tensor_name: String,
     pub recommended_resolution: (u32, u32),
     pub style_description: String,
+    /// Per-model tensor normalization; see `TensorInfo`.
+    pub tensor_info: TensorInfo,
+}
+
+/// Describes how `crate::preprocessing` should normalize RGBA bytes into (and
+/// back out of) this model's NCHW tensor. Each byte is mapped via
+/// `(byte / 255.0) * scale + bias`; different exported graphs expect different
+/// input ranges (e.g. `[0,1]` vs `[-1,1]`), so this is configurable per model
+/// rather than hardcoded.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+pub struct TensorInfo {
+    pub scale: f32,
+    pub bias: f32,
+    /// NCHW shape (batch, channels, height, width) this model's graph expects;
+    /// used to set the tract CPU backend's input fact.
+    pub shape: (u32, u32, u32, u32),
+}
+
+impl Default for TensorInfo {
+    fn default() -> Self {
+        // Maps [0,255] -> [-1,1], the normalization most exported style-transfer
+        // graphs expect.
+        TensorInfo { scale: 2.0, bias: -1.0, shape: (1, 3, 256, 256) }
+    }
+}
+
+/// Which backend `ONNXStyleTransferEngine` should run inference on. `Auto` tries
+/// a WebGPU (wonnx) session first and transparently falls back to the CPU
+/// (tract-onnx) backend if no adapter is available or the graph fails to load.
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    WebGpu,
+    Cpu,
+    Auto,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -24,15 +65,26 @@ pub struct StyleTransferResult {
 pub struct ONNXStyleTransferEngine {
     models: HashMap<String, Vec<u8>>,
     model_metadata: HashMap<String, ModelMetadata>,
+    // WebGPU-backed wonnx sessions, parsed once per style the first time
+    // `load_model` is called for it and reused by every subsequent
+    // `apply_style_transfer`.
+    sessions: HashMap<String, WonnxSession>,
+    // CPU (tract-onnx) fallback, populated instead of `sessions` when `backend`
+    // is `Cpu`, or when `Auto` couldn't get a WebGPU session.
+    cpu_models: HashMap<String, TractModel>,
+    backend: Backend,
 }
 
 #[wasm_bindgen]
 impl ONNXStyleTransferEngine {
     #[wasm_bindgen(constructor)]
-    pub fn new() -> ONNXStyleTransferEngine {
+    pub fn new(backend: Backend) -> ONNXStyleTransferEngine {
         ONNXStyleTransferEngine {
             models: HashMap::new(),
             model_metadata: HashMap::new(),
+            sessions: HashMap::new(),
+            cpu_models: HashMap::new(),
+            backend,
         }
     }
 
@@ -42,14 +94,56 @@ impl ONNXStyleTransferEngine {
         Ok(())
     }
 
-    pub fn load_model(&mut self, style_name: &str) -> Result<(), JsValue> {
-        if !self.models.contains_key(style_name) {
-            return Err(format!("Model '{}' not found", style_name).into());
+    pub async fn load_model(&mut self, style_name: &str) -> Result<(), JsValue> {
+        if self.sessions.contains_key(style_name) || self.cpu_models.contains_key(style_name) {
+            return Ok(()); // Already loaded
+        }
+
+        let model_data = self.models.get(style_name)
+            .ok_or_else(|| JsValue::from_str(&format!("Model '{}' not found", style_name)))?
+            .clone();
+        let metadata = self.model_metadata.get(style_name)
+            .ok_or_else(|| JsValue::from_str(&format!("Model '{}' metadata not found", style_name)))?
+            .clone();
+
+        if matches!(self.backend, Backend::WebGpu | Backend::Auto) {
+            match WonnxSession::from_bytes(&model_data).await {
+                Ok(session) => {
+                    self.sessions.insert(style_name.to_string(), session);
+                    return Ok(());
+                }
+                Err(e) if self.backend == Backend::WebGpu => {
+                    return Err(JsValue::from_str(&format!("wonnx session creation failed for '{}': {}", style_name, e)));
+                }
+                Err(e) => {
+                    web_sys::console::warn_1(&format!(
+                        "WebGPU backend unavailable for '{}' ({}); falling back to CPU (tract-onnx)",
+                        style_name, e,
+                    ).into());
+                }
+            }
         }
-        
+
+        let cpu_model = Self::build_tract_model(&model_data, &metadata.tensor_info)
+            .map_err(|e| JsValue::from_str(&format!("tract-onnx model build failed for '{}': {}", style_name, e)))?;
+        self.cpu_models.insert(style_name.to_string(), cpu_model);
+
         Ok(())
     }
 
+    /// Parses the ONNX `ModelProto` bytes into a runnable tract `TypedModel`,
+    /// fixing its input fact to `tensor_info.shape` so tract can fully infer and
+    /// optimize the graph ahead of time.
+    fn build_tract_model(model_data: &[u8], tensor_info: &TensorInfo) -> TractResult<TractModel> {
+        let (n, c, h, w) = tensor_info.shape;
+        let mut cursor = std::io::Cursor::new(model_data);
+        tract_onnx::onnx()
+            .model_for_read(&mut cursor)?
+            .with_input_fact(0, InferenceFact::dt_shape(f32::datum_type(), tvec!(n as usize, c as usize, h as usize, w as usize)))?
+            .into_optimized()?
+            .into_runnable()
+    }
+
     pub fn get_available_styles(&self) -> Result<JsValue, JsValue> {
         let styles: Vec<String> = self.models.keys().cloned().collect();
         serde_wasm_bindgen::to_value(&styles)
@@ -65,183 +159,139 @@ impl ONNXStyleTransferEngine {
         }
     }
 
-    pub fn apply_style_transfer(
+    pub async fn apply_style_transfer(
         &self,
         input_image_data: &[u8],
-        _width: u32,
-        _height: u32,
+        width: u32,
+        height: u32,
         style_strength: f32,
         style_name: &str,
     ) -> Result<JsValue, JsValue> {
         let start_time = js_sys::Date::now();
-        
+
         // Model existence is checked by ModelRegistry, so we can proceed directly
+        let metadata = self.model_metadata.get(style_name)
+            .ok_or_else(|| JsValue::from_str(&format!("Model '{}' metadata not found", style_name)))?;
+
+        // Run the actual ONNX graph: WebGPU through wonnx when a session was
+        // built for this style, the tract-onnx CPU backend otherwise.
+        let output_data = if let Some(session) = self.sessions.get(style_name) {
+            self.run_wonnx_inference(
+                session, metadata, input_image_data, width, height, style_strength,
+            ).await?
+        } else if let Some(cpu_model) = self.cpu_models.get(style_name) {
+            self.run_tract_inference(
+                cpu_model, metadata, input_image_data, width, height, style_strength,
+            )?
+        } else {
+            return Err(JsValue::from_str(&format!("Model '{}' not loaded. Call load_model() first.", style_name)));
+        };
 
-        // Apply neural style transfer using advanced algorithms
-        let output_data = self.run_neural_style_transfer(input_image_data, style_strength, style_name);
-        
         let end_time = js_sys::Date::now();
         let processing_time = end_time - start_time;
-        
+
         let result = StyleTransferResult {
             success: true,
             output_data: Some(output_data),
             error_message: None,
             processing_time_ms: processing_time,
         };
-        
+
         serde_wasm_bindgen::to_value(&result)
             .map_err(|e| format!("Serialization failed: {}", e).into())
     }
 
-    fn run_neural_style_transfer(
+    /// Preprocesses, runs, and postprocesses one real inference pass: resizes
+    /// `input_image_data` to the model's `recommended_resolution`, packs it into an
+    /// NCHW tensor, feeds it through `session` bound to `metadata.input_tensor_name`,
+    /// reads the result back from `metadata.output_tensor_name`, resizes it back to
+    /// the caller's resolution, and linearly blends it against the original by
+    /// `style_strength` (0-100) so existing callers see the same blend knob.
+    async fn run_wonnx_inference(
         &self,
+        session: &WonnxSession,
+        metadata: &ModelMetadata,
         input_image_data: &[u8],
+        width: u32,
+        height: u32,
         style_strength: f32,
-        style_name: &str,
-    ) -> Vec<u8> {
-        let mut output_data = input_image_data.to_vec();
-        let strength_factor = style_strength / 100.0;
-        
-        match style_name {
-            "van-gogh" => {
-                // Van Gogh: Impressionist style with texture simulation
-                for i in (0..output_data.len()).step_by(4) {
-                    if i + 2 < output_data.len() {
-                        let r = output_data[i] as f32;
-                        let g = output_data[i + 1] as f32;
-                        let b = output_data[i + 2] as f32;
-                        
-                        // Warm color enhancement with texture
-                        let enhanced_r = r * (1.0 + strength_factor * 0.4) + strength_factor * 20.0;
-                        let enhanced_g = g * (1.0 + strength_factor * 0.3) + strength_factor * 15.0;
-                        let reduced_b = b * (1.0 - strength_factor * 0.3) - strength_factor * 10.0;
-                        
-                        // Add texture variation
-                        let texture = (i as f32 * 0.1).sin() * strength_factor * 15.0;
-                        
-                        output_data[i] = (enhanced_r + texture).clamp(0.0, 255.0) as u8;
-                        output_data[i + 1] = (enhanced_g + texture).clamp(0.0, 255.0) as u8;
-                        output_data[i + 2] = (reduced_b + texture).clamp(0.0, 255.0) as u8;
-                    }
-                }
-            },
-            "picasso" => {
-                // Picasso: Cubist geometric abstraction
-                for i in (0..output_data.len()).step_by(4) {
-                    if i + 2 < output_data.len() {
-                        let r = output_data[i] as f32;
-                        let g = output_data[i + 1] as f32;
-                        let b = output_data[i + 2] as f32;
-                        
-                        // Geometric color shifts
-                        let reduced_r = r * (1.0 - strength_factor * 0.4);
-                        let enhanced_g = g * (1.0 + strength_factor * 0.5) + strength_factor * 25.0;
-                        let enhanced_b = b * (1.0 + strength_factor * 0.4) + strength_factor * 20.0;
-                        
-                        // Add geometric patterns
-                        let pattern = if (i / 4) % 2 == 0 { 1.0 } else { 0.8 };
-                        
-                        output_data[i] = (reduced_r * pattern).clamp(0.0, 255.0) as u8;
-                        output_data[i + 1] = (enhanced_g * pattern).clamp(0.0, 255.0) as u8;
-                        output_data[i + 2] = (enhanced_b * pattern).clamp(0.0, 255.0) as u8;
-                    }
-                }
-            },
-            "cyberpunk" => {
-                // Cyberpunk: Futuristic neon aesthetics
-                for i in (0..output_data.len()).step_by(4) {
-                    if i + 2 < output_data.len() {
-                        let r = output_data[i] as f32;
-                        let g = output_data[i + 1] as f32;
-                        let b = output_data[i + 2] as f32;
-                        
-                        // Strong neon colors
-                        let neon_r = r * (1.0 + strength_factor * 0.8) + strength_factor * 40.0;
-                        let reduced_g = g * (1.0 - strength_factor * 0.5);
-                        let neon_b = b * (1.0 + strength_factor * 1.0) + strength_factor * 50.0;
-                        
-                        // Add neon glow effect
-                        let glow = (i as f32 * 0.05).sin() * strength_factor * 30.0;
-                        
-                        output_data[i] = (neon_r + glow).clamp(0.0, 255.0) as u8;
-                        output_data[i + 1] = (reduced_g + glow * 0.3).clamp(0.0, 255.0) as u8;
-                        output_data[i + 2] = (neon_b + glow).clamp(0.0, 255.0) as u8;
-                    }
-                }
-            },
-            "watercolor" => {
-                // Watercolor: Soft, flowing effects
-                for i in (0..output_data.len()).step_by(4) {
-                    if i + 2 < output_data.len() {
-                        let r = output_data[i] as f32;
-                        let g = output_data[i + 1] as f32;
-                        let b = output_data[i + 2] as f32;
-                        
-                        // Soft color enhancement
-                        let soft_r = r * (1.0 + strength_factor * 0.2) + strength_factor * 10.0;
-                        let soft_g = g * (1.0 + strength_factor * 0.3) + strength_factor * 15.0;
-                        let soft_b = b * (1.0 + strength_factor * 0.2) + strength_factor * 10.0;
-                        
-                        // Add watercolor flow effect
-                        let flow = (i as f32 * 0.02).sin() * strength_factor * 20.0;
-                        
-                        output_data[i] = (soft_r + flow).clamp(0.0, 255.0) as u8;
-                        output_data[i + 1] = (soft_g + flow).clamp(0.0, 255.0) as u8;
-                        output_data[i + 2] = (soft_b + flow).clamp(0.0, 255.0) as u8;
-                    }
-                }
-            },
-            "oil-painting" => {
-                // Oil Painting: Rich, textured appearance
-                for i in (0..output_data.len()).step_by(4) {
-                    if i + 2 < output_data.len() {
-                        let r = output_data[i] as f32;
-                        let g = output_data[i + 1] as f32;
-                        let b = output_data[i + 2] as f32;
-                        
-                        // Rich color saturation
-                        let rich_r = r * (1.0 + strength_factor * 0.6) + strength_factor * 30.0;
-                        let rich_g = g * (1.0 + strength_factor * 0.5) + strength_factor * 25.0;
-                        let rich_b = b * (1.0 + strength_factor * 0.4) + strength_factor * 20.0;
-                        
-                        // Add oil painting texture
-                        let texture = (i as f32 * 0.03).cos() * strength_factor * 25.0;
-                        
-                        output_data[i] = (rich_r + texture).clamp(0.0, 255.0) as u8;
-                        output_data[i + 1] = (rich_g + texture).clamp(0.0, 255.0) as u8;
-                        output_data[i + 2] = (rich_b + texture).clamp(0.0, 255.0) as u8;
-                    }
-                }
-            },
-            _ => {
-                // Default: intelligent enhancement with strength control
-                for i in (0..output_data.len()).step_by(4) {
-                    if i + 2 < output_data.len() {
-                        let r = output_data[i] as f32;
-                        let g = output_data[i + 1] as f32;
-                        let b = output_data[i + 2] as f32;
-                        
-                        // Adaptive enhancement based on current pixel values
-                        let brightness = (r + g + b) / 3.0;
-                        let enhancement = if brightness < 128.0 { 
-                            strength_factor * 0.3 
-                        } else { 
-                            strength_factor * 0.1 
-                        };
-                        
-                        let enhanced_r = r * (1.0 + enhancement);
-                        let enhanced_g = g * (1.0 + enhancement);
-                        let enhanced_b = b * (1.0 + enhancement);
-                        
-                        output_data[i] = enhanced_r.clamp(0.0, 255.0) as u8;
-                        output_data[i + 1] = enhanced_g.clamp(0.0, 255.0) as u8;
-                        output_data[i + 2] = enhanced_b.clamp(0.0, 255.0) as u8;
-                    }
-                }
+    ) -> Result<Vec<u8>, JsValue> {
+        let (target_width, target_height) = metadata.recommended_resolution;
+
+        let input_tensor = crate::preprocessing::rgba_to_nchw(
+            input_image_data, width, height, target_width, target_height, &metadata.tensor_info,
+        );
+
+        let mut outputs = session
+            .run(vec![(metadata.input_tensor_name.clone(), input_tensor)])
+            .await
+            .map_err(|e| JsValue::from_str(&format!("wonnx inference failed for '{}': {}", metadata.name, e)))?;
+
+        let output_tensor = outputs
+            .remove(&metadata.output_tensor_name)
+            .ok_or_else(|| JsValue::from_str(&format!(
+                "wonnx output tensor '{}' missing for model '{}'",
+                metadata.output_tensor_name, metadata.name,
+            )))?;
+
+        let stylized = crate::preprocessing::nchw_to_rgba(
+            &output_tensor, target_width, target_height, width, height, &metadata.tensor_info, input_image_data,
+        );
+
+        Ok(Self::blend_with_original(input_image_data, &stylized, style_strength))
+    }
+
+    /// CPU fallback: same preprocess/postprocess pipeline as `run_wonnx_inference`,
+    /// but runs the graph through the tract `TractModel` built by
+    /// `build_tract_model` instead of a WebGPU wonnx session.
+    fn run_tract_inference(
+        &self,
+        model: &TractModel,
+        metadata: &ModelMetadata,
+        input_image_data: &[u8],
+        width: u32,
+        height: u32,
+        style_strength: f32,
+    ) -> Result<Vec<u8>, JsValue> {
+        let (target_width, target_height) = metadata.recommended_resolution;
+
+        let input_tensor = crate::preprocessing::rgba_to_nchw(
+            input_image_data, width, height, target_width, target_height, &metadata.tensor_info,
+        );
+
+        let (n, c, h, w) = metadata.tensor_info.shape;
+        let tract_input = Tensor::from_shape(&[n as usize, c as usize, h as usize, w as usize], &input_tensor)
+            .map_err(|e| JsValue::from_str(&format!("tract tensor shape error for '{}': {}", metadata.name, e)))?;
+
+        let outputs = model
+            .run(tvec!(tract_input.into()))
+            .map_err(|e| JsValue::from_str(&format!("tract-onnx inference failed for '{}': {}", metadata.name, e)))?;
+
+        let output_view = outputs[0]
+            .to_array_view::<f32>()
+            .map_err(|e| JsValue::from_str(&format!("tract-onnx output extraction failed for '{}': {}", metadata.name, e)))?;
+        let output_tensor: Vec<f32> = output_view.iter().cloned().collect();
+
+        let stylized = crate::preprocessing::nchw_to_rgba(
+            &output_tensor, target_width, target_height, width, height, &metadata.tensor_info, input_image_data,
+        );
+
+        Ok(Self::blend_with_original(input_image_data, &stylized, style_strength))
+    }
+
+    /// Linearly interpolates between `original` and `stylized` by `strength`
+    /// (0-100), keeping the original alpha channel.
+    fn blend_with_original(original: &[u8], stylized: &[u8], strength: f32) -> Vec<u8> {
+        let t = (strength / 100.0).clamp(0.0, 1.0);
+        let mut out = original.to_vec();
+        for i in (0..out.len()).step_by(4) {
+            if i + 2 >= stylized.len() {
+                break;
             }
+            out[i] = ((1.0 - t) * original[i] as f32 + t * stylized[i] as f32).round() as u8;
+            out[i + 1] = ((1.0 - t) * original[i + 1] as f32 + t * stylized[i + 1] as f32).round() as u8;
+            out[i + 2] = ((1.0 - t) * original[i + 2] as f32 + t * stylized[i + 2] as f32).round() as u8;
         }
-        
-        output_data
+        out
     }
 }
@@ -1,21 +1,91 @@
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
 use web_sys::*;
 
+pub mod preprocess;
+
+/// Thin wrapper around a negotiated `navigator.gpu` adapter/device pair. This is the
+/// foundational subsystem the rest of the GPU work (inference, preprocessing
+/// shaders, tensor ops) builds on: callers ask `is_available()` before choosing the
+/// GPU path and fall back to CPU otherwise.
 pub struct GpuContext {
-    // For now, we'll use a mock GPU context since WebGPU is not yet stable
-    // In the future, this will be replaced with actual WebGPU implementation
+    adapter: Option<GpuAdapter>,
+    device: Option<GpuDevice>,
+    queue: Option<GpuQueue>,
 }
 
 impl GpuContext {
     pub async fn new() -> Result<Self, JsValue> {
-        // Mock GPU context for now
-        // TODO: Implement actual WebGPU when it becomes stable
-        Ok(Self {})
+        let mut ctx = Self {
+            adapter: None,
+            device: None,
+            queue: None,
+        };
+
+        if let Err(e) = ctx.request_device().await {
+            web_sys::console::warn_1(&format!("WebGPU unavailable: {:?}", e).into());
+        }
+
+        Ok(ctx)
     }
-    
+
+    async fn request_device(&mut self) -> Result<(), JsValue> {
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("no global `window` exists"))?;
+        let navigator = window.navigator();
+
+        let gpu: Gpu = js_sys::Reflect::get(&navigator, &JsValue::from_str("gpu"))?
+            .dyn_into()
+            .map_err(|_| JsValue::from_str("navigator.gpu is not present"))?;
+
+        let adapter_value = JsFuture::from(gpu.request_adapter()).await?;
+        if adapter_value.is_null() || adapter_value.is_undefined() {
+            return Err(JsValue::from_str("navigator.gpu.requestAdapter() returned null"));
+        }
+        let adapter: GpuAdapter = adapter_value.dyn_into()?;
+
+        let device_value = JsFuture::from(adapter.request_device()).await?;
+        let device: GpuDevice = device_value.dyn_into()?;
+        let queue = device.queue();
+
+        self.adapter = Some(adapter);
+        self.device = Some(device);
+        self.queue = Some(queue);
+
+        Ok(())
+    }
+
+    /// True only when an adapter and device were actually acquired.
     pub fn is_available(&self) -> bool {
-        // For now, return false since WebGPU is not yet stable
-        // TODO: Implement actual WebGPU detection when it becomes stable
-        false
+        self.adapter.is_some() && self.device.is_some()
+    }
+
+    pub fn device(&self) -> Option<&GpuDevice> {
+        self.device.as_ref()
+    }
+
+    pub fn queue(&self) -> Option<&GpuQueue> {
+        self.queue.as_ref()
+    }
+
+    pub fn adapter_info(&self) -> Option<GpuAdapterInfo> {
+        self.adapter.as_ref().map(|a| a.info())
+    }
+
+    /// Enumerate the acquired adapter's features and limits so callers can decide
+    /// whether to take the GPU inference/preprocessing path or fall back to CPU.
+    pub fn supported_features(&self) -> Vec<String> {
+        let Some(adapter) = self.adapter.as_ref() else {
+            return Vec::new();
+        };
+
+        let features = adapter.features();
+        let iter = js_sys::try_iter(&features).ok().flatten();
+        let Some(iter) = iter else {
+            return Vec::new();
+        };
+
+        iter.filter_map(|entry| entry.ok())
+            .filter_map(|v| v.as_string())
+            .collect()
     }
 }
@@ -0,0 +1,271 @@
+use super::GpuContext;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{GpuBuffer, GpuBufferDescriptor, GpuBufferUsage, GpuMapMode};
+
+/// WGSL kernel that does RGBA->RGB, resize, [0,1] normalization, and HWC->CHW
+/// packing in a single dispatch. One invocation per *output* pixel.
+const PREPROCESS_SHADER: &str = r#"
+struct Dims {
+    src_w: u32,
+    src_h: u32,
+    dst_w: u32,
+    dst_h: u32,
+};
+
+@group(0) @binding(0) var<storage, read> src_rgba: array<u32>;
+@group(0) @binding(1) var<storage, read_write> dst_chw: array<f32>;
+@group(0) @binding(2) var<uniform> dims: Dims;
+
+fn read_channel(pixel: u32, channel: u32) -> f32 {
+    let word = src_rgba[pixel];
+    let shifted = word >> (channel * 8u);
+    return f32(shifted & 0xFFu);
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let x = gid.x;
+    let y = gid.y;
+    if (x >= dims.dst_w || y >= dims.dst_h) {
+        return;
+    }
+
+    let src_x = min(u32(f32(x) * f32(dims.src_w) / f32(dims.dst_w)), dims.src_w - 1u);
+    let src_y = min(u32(f32(y) * f32(dims.src_h) / f32(dims.dst_h)), dims.src_h - 1u);
+    let src_pixel = src_y * dims.src_w + src_x;
+
+    let plane = dims.dst_w * dims.dst_h;
+    let dst_idx = y * dims.dst_w + x;
+
+    dst_chw[dst_idx] = read_channel(src_pixel, 0u) / 255.0;
+    dst_chw[plane + dst_idx] = read_channel(src_pixel, 1u) / 255.0;
+    dst_chw[plane * 2u + dst_idx] = read_channel(src_pixel, 2u) / 255.0;
+}
+"#;
+
+/// WGSL kernel for the reverse: CHW f32 -> RGBA u8, denormalize and clamp, plus the
+/// `blend_with_original` alpha blend against the original image (already resized to
+/// the output resolution on the CPU side since that's a cheap copy).
+const POSTPROCESS_SHADER: &str = r#"
+struct Dims {
+    width: u32,
+    height: u32,
+    strength_bits: u32,
+};
+
+@group(0) @binding(0) var<storage, read> src_chw: array<f32>;
+@group(0) @binding(1) var<storage, read> original_rgba: array<u32>;
+@group(0) @binding(2) var<storage, read_write> dst_rgba: array<u32>;
+@group(0) @binding(3) var<uniform> dims: Dims;
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let x = gid.x;
+    let y = gid.y;
+    if (x >= dims.width || y >= dims.height) {
+        return;
+    }
+
+    let plane = dims.width * dims.height;
+    let idx = y * dims.width + x;
+
+    let r = clamp(src_chw[idx] * 255.0, 0.0, 255.0);
+    let g = clamp(src_chw[plane + idx] * 255.0, 0.0, 255.0);
+    let b = clamp(src_chw[plane * 2u + idx] * 255.0, 0.0, 255.0);
+
+    let strength = bitcast<f32>(dims.strength_bits);
+    let orig = original_rgba[idx];
+    let orig_r = f32(orig & 0xFFu);
+    let orig_g = f32((orig >> 8u) & 0xFFu);
+    let orig_b = f32((orig >> 16u) & 0xFFu);
+
+    let blended_r = u32(orig_r * (1.0 - strength) + r * strength);
+    let blended_g = u32(orig_g * (1.0 - strength) + g * strength);
+    let blended_b = u32(orig_b * (1.0 - strength) + b * strength);
+
+    dst_rgba[idx] = blended_r | (blended_g << 8u) | (blended_b << 16u) | (255u << 24u);
+}
+"#;
+
+/// GPU-resident RGBA -> normalized CHW tensor pipeline: resize, [0,1] normalize, and
+/// HWC->CHW pack in one dispatch. Falls back to the caller's CPU path is the
+/// responsibility of the caller (this function assumes `ctx.is_available()`).
+pub async fn preprocess_gpu(
+    ctx: &GpuContext,
+    rgba: &[u8],
+    src_w: u32,
+    src_h: u32,
+    dst_w: u32,
+    dst_h: u32,
+) -> Result<Vec<f32>, JsValue> {
+    let device = ctx.device().ok_or_else(|| JsValue::from_str("GpuContext has no device"))?;
+    let queue = ctx.queue().ok_or_else(|| JsValue::from_str("GpuContext has no queue"))?;
+
+    let src_words: Vec<u32> = rgba
+        .chunks_exact(4)
+        .map(|p| u32::from_le_bytes([p[0], p[1], p[2], p[3]]))
+        .collect();
+
+    let shader_module = device.create_shader_module(&web_sys::GpuShaderModuleDescriptor::new(PREPROCESS_SHADER));
+
+    let src_bytes: Vec<u8> = src_words.iter().flat_map(|w| w.to_le_bytes()).collect();
+    let src_buf = create_upload_buffer(device, queue, &src_bytes, GpuBufferUsage::STORAGE)?;
+
+    let dims_bytes: Vec<u8> = [src_w, src_h, dst_w, dst_h].iter().flat_map(|v| v.to_le_bytes()).collect();
+    let dims_buf = create_upload_buffer(device, queue, &dims_bytes, GpuBufferUsage::UNIFORM)?;
+
+    let out_len = (dst_w * dst_h * 3) as usize;
+    let out_byte_len = (out_len * std::mem::size_of::<f32>()) as f64;
+    let output_buf: GpuBuffer = device.create_buffer(&GpuBufferDescriptor::new(
+        out_byte_len,
+        GpuBufferUsage::STORAGE | GpuBufferUsage::COPY_SRC,
+    ));
+    let readback_buf: GpuBuffer = device.create_buffer(&GpuBufferDescriptor::new(
+        out_byte_len,
+        GpuBufferUsage::COPY_DST | GpuBufferUsage::MAP_READ,
+    ));
+
+    dispatch(
+        device,
+        queue,
+        &shader_module,
+        &[&src_buf, &output_buf, &dims_buf],
+        ((dst_w + 7) / 8, (dst_h + 7) / 8, 1),
+        &output_buf,
+        &readback_buf,
+        out_byte_len,
+    )?;
+
+    read_f32_buffer(&readback_buf, out_len).await
+}
+
+/// GPU-resident normalized-CHW -> RGBA with denormalize/clamp and the
+/// `strength`-weighted blend against the (already-resized-to-output) original.
+pub async fn postprocess_gpu(
+    ctx: &GpuContext,
+    tensor: &[f32],
+    original_rgba_resized: &[u8],
+    width: u32,
+    height: u32,
+    strength: f32,
+) -> Result<Vec<u8>, JsValue> {
+    let device = ctx.device().ok_or_else(|| JsValue::from_str("GpuContext has no device"))?;
+    let queue = ctx.queue().ok_or_else(|| JsValue::from_str("GpuContext has no queue"))?;
+
+    let shader_module = device.create_shader_module(&web_sys::GpuShaderModuleDescriptor::new(POSTPROCESS_SHADER));
+
+    let tensor_bytes: Vec<u8> = tensor.iter().flat_map(|v| v.to_le_bytes()).collect();
+    let tensor_buf = create_upload_buffer(device, queue, &tensor_bytes, GpuBufferUsage::STORAGE)?;
+
+    let original_words: Vec<u32> = original_rgba_resized
+        .chunks_exact(4)
+        .map(|p| u32::from_le_bytes([p[0], p[1], p[2], p[3]]))
+        .collect();
+    let original_bytes: Vec<u8> = original_words.iter().flat_map(|w| w.to_le_bytes()).collect();
+    let original_buf = create_upload_buffer(device, queue, &original_bytes, GpuBufferUsage::STORAGE)?;
+
+    let dims_bytes: Vec<u8> = [width, height, strength.clamp(0.0, 1.0).to_bits()]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect();
+    let dims_buf = create_upload_buffer(device, queue, &dims_bytes, GpuBufferUsage::UNIFORM)?;
+
+    let out_len = (width * height) as usize;
+    let out_byte_len = (out_len * std::mem::size_of::<u32>()) as f64;
+    let output_buf: GpuBuffer = device.create_buffer(&GpuBufferDescriptor::new(
+        out_byte_len,
+        GpuBufferUsage::STORAGE | GpuBufferUsage::COPY_SRC,
+    ));
+    let readback_buf: GpuBuffer = device.create_buffer(&GpuBufferDescriptor::new(
+        out_byte_len,
+        GpuBufferUsage::COPY_DST | GpuBufferUsage::MAP_READ,
+    ));
+
+    dispatch(
+        device,
+        queue,
+        &shader_module,
+        &[&tensor_buf, &original_buf, &output_buf, &dims_buf],
+        ((width + 7) / 8, (height + 7) / 8, 1),
+        &output_buf,
+        &readback_buf,
+        out_byte_len,
+    )?;
+
+    let words = read_u32_buffer(&readback_buf, out_len).await?;
+    Ok(words.iter().flat_map(|w| w.to_le_bytes()).collect())
+}
+
+fn create_upload_buffer(
+    device: &web_sys::GpuDevice,
+    queue: &web_sys::GpuQueue,
+    bytes: &[u8],
+    usage: u32,
+) -> Result<GpuBuffer, JsValue> {
+    let aligned_len = ((bytes.len() + 3) / 4 * 4) as f64;
+    let buf = device.create_buffer(&GpuBufferDescriptor::new(aligned_len, usage | GpuBufferUsage::COPY_DST));
+    queue.write_buffer_with_u32_and_u8_slice(&buf, 0, bytes);
+    Ok(buf)
+}
+
+/// Dispatch `shader_module` over `workgroups`, then (map-on-completion pattern)
+/// copy the storage output buffer into the MAP_READ readback buffer in the same
+/// command submission. Byte lengths must already be 4-aligned, and `output_buf`
+/// must not be read on the host until after this copy lands.
+fn dispatch(
+    device: &web_sys::GpuDevice,
+    queue: &web_sys::GpuQueue,
+    shader_module: &web_sys::GpuShaderModule,
+    bindings: &[&GpuBuffer],
+    workgroups: (u32, u32, u32),
+    output_buf: &GpuBuffer,
+    readback_buf: &GpuBuffer,
+    byte_len: f64,
+) -> Result<(), JsValue> {
+    let pipeline = device.create_compute_pipeline(&web_sys::GpuComputePipelineDescriptor::new(
+        &"auto".into(),
+        &web_sys::GpuProgrammableStage::new(shader_module),
+    ));
+    let bind_group_layout = pipeline.get_bind_group_layout(0);
+
+    let entries = js_sys::Array::new();
+    for (i, buf) in bindings.iter().enumerate() {
+        entries.push(&web_sys::GpuBindGroupEntry::new(i as u32, *buf).into());
+    }
+    let bind_group = device.create_bind_group(&web_sys::GpuBindGroupDescriptor::new(&entries, &bind_group_layout));
+
+    let encoder = device.create_command_encoder();
+    let pass = encoder.begin_compute_pass();
+    pass.set_pipeline(&pipeline);
+    pass.set_bind_group(0, &bind_group);
+    pass.dispatch_workgroups_with_workgroup_count_y_and_workgroup_count_z(workgroups.0, workgroups.1, workgroups.2);
+    pass.end();
+    encoder.copy_buffer_to_buffer_with_u32_and_u32_and_u32(output_buf, 0, readback_buf, 0, byte_len as u32);
+    queue.submit(&js_sys::Array::of1(&encoder.finish()));
+    Ok(())
+}
+
+async fn read_f32_buffer(readback_buf: &GpuBuffer, out_len: usize) -> Result<Vec<f32>, JsValue> {
+    JsFuture::from(readback_buf.map_async(GpuMapMode::READ)).await?;
+    let mapped = readback_buf.get_mapped_range();
+    let bytes = js_sys::Uint8Array::new(&mapped).to_vec();
+    readback_buf.unmap();
+    Ok(bytes
+        .chunks_exact(4)
+        .take(out_len)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+async fn read_u32_buffer(readback_buf: &GpuBuffer, out_len: usize) -> Result<Vec<u32>, JsValue> {
+    JsFuture::from(readback_buf.map_async(GpuMapMode::READ)).await?;
+    let mapped = readback_buf.get_mapped_range();
+    let bytes = js_sys::Uint8Array::new(&mapped).to_vec();
+    readback_buf.unmap();
+    Ok(bytes
+        .chunks_exact(4)
+        .take(out_len)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
@@ -1,6 +1,9 @@
 use wasm_bindgen::prelude::*;
 use ndarray::Array4;
 
+use crate::resampling::{resize_rgba, ResampleMode};
+use crate::utils::image_filters::ImageFilters;
+
 pub struct ImageProcessor;
 
 impl ImageProcessor {
@@ -14,46 +17,20 @@ impl ImageProcessor {
         width: u32,
         height: u32,
         target_size: usize,
+        mode: ResampleMode,
     ) -> Result<Vec<f32>, JsValue> {
-        let mut processed = Vec::with_capacity(target_size * target_size * 3);
-        
-        // Convert RGBA to RGB and resize to target size
-        let scale_x = width as f32 / target_size as f32;
-        let scale_y = height as f32 / target_size as f32;
-
-        for y in 0..target_size {
-            for x in 0..target_size {
-                let src_x = (x as f32 * scale_x) as u32;
-                let src_y = (y as f32 * scale_y) as u32;
-                
-                let src_idx = ((src_y * width + src_x) * 4) as usize;
-                
-                if src_idx + 2 < image_data.len() {
-                    // Normalize to [-1, 1] range for neural network
-                    let r = (image_data[src_idx] as f32 / 255.0) * 2.0 - 1.0;
-                    let g = (image_data[src_idx + 1] as f32 / 255.0) * 2.0 - 1.0;
-                    let b = (image_data[src_idx + 2] as f32 / 255.0) * 2.0 - 1.0;
-                    
-                    processed.push(r);
-                    processed.push(g);
-                    processed.push(b);
-                } else {
-                    // Handle edge case with padding
-                    processed.push(0.0);
-                    processed.push(0.0);
-                    processed.push(0.0);
-                }
-            }
-        }
+        let target = target_size as u32;
+        let resized = resize_rgba(image_data, width, height, target, target, mode);
 
-        // Reorganize from HWC to CHW format
+        // Reorganize from HWC to CHW format, normalizing to [-1, 1] for the network.
         let mut chw_data = vec![0.0; target_size * target_size * 3];
         let hw_size = target_size * target_size;
-        
-        for i in 0..(target_size * target_size) {
-            chw_data[i] = processed[i * 3];                    // R channel
-            chw_data[i + hw_size] = processed[i * 3 + 1];      // G channel  
-            chw_data[i + 2 * hw_size] = processed[i * 3 + 2];  // B channel
+
+        for i in 0..hw_size {
+            let idx = i * 4;
+            chw_data[i] = (resized[idx] as f32 / 255.0) * 2.0 - 1.0;              // R channel
+            chw_data[i + hw_size] = (resized[idx + 1] as f32 / 255.0) * 2.0 - 1.0;     // G channel
+            chw_data[i + 2 * hw_size] = (resized[idx + 2] as f32 / 255.0) * 2.0 - 1.0; // B channel
         }
 
         Ok(chw_data)
@@ -64,45 +41,34 @@ impl ImageProcessor {
         output_tensor: &Array4<f32>,
         target_width: u32,
         target_height: u32,
+        mode: ResampleMode,
     ) -> Result<Vec<u8>, JsValue> {
         let shape = output_tensor.shape();
         if shape.len() != 4 || shape[0] != 1 || shape[1] != 3 {
             return Err(JsValue::from_str("Invalid output tensor shape"));
         }
 
-        let model_size = shape[2];
-        let mut result = Vec::with_capacity((target_width * target_height * 4) as usize);
-
-        // Scale factors for resizing
-        let scale_x = model_size as f32 / target_width as f32;
-        let scale_y = model_size as f32 / target_height as f32;
-
-        for y in 0..target_height {
-            for x in 0..target_width {
-                let src_x = (x as f32 * scale_x) as usize;
-                let src_y = (y as f32 * scale_y) as usize;
-
-                if src_x < model_size && src_y < model_size {
-                    // Get RGB values from CHW tensor format
-                    let r_val = output_tensor[[0, 0, src_y, src_x]];
-                    let g_val = output_tensor[[0, 1, src_y, src_x]];
-                    let b_val = output_tensor[[0, 2, src_y, src_x]];
-
-                    // Convert from [-1, 1] range back to [0, 255]
-                    let r = ((r_val + 1.0) * 127.5).clamp(0.0, 255.0) as u8;
-                    let g = ((g_val + 1.0) * 127.5).clamp(0.0, 255.0) as u8;
-                    let b = ((b_val + 1.0) * 127.5).clamp(0.0, 255.0) as u8;
-                    let a = 255u8;
-
-                    result.extend_from_slice(&[r, g, b, a]);
-                } else {
-                    // Padding for out-of-bounds
-                    result.extend_from_slice(&[0, 0, 0, 255]);
-                }
+        let model_size = shape[2] as u32;
+
+        // Denormalize the model's CHW tensor into an HWC RGBA buffer at the
+        // model's native resolution, then upscale it to the caller's target
+        // resolution with `mode` instead of nearest-neighbor.
+        let mut native = vec![0u8; (model_size * model_size * 4) as usize];
+        for y in 0..model_size as usize {
+            for x in 0..model_size as usize {
+                let r_val = output_tensor[[0, 0, y, x]];
+                let g_val = output_tensor[[0, 1, y, x]];
+                let b_val = output_tensor[[0, 2, y, x]];
+
+                let idx = (y * model_size as usize + x) * 4;
+                native[idx] = ((r_val + 1.0) * 127.5).clamp(0.0, 255.0) as u8;
+                native[idx + 1] = ((g_val + 1.0) * 127.5).clamp(0.0, 255.0) as u8;
+                native[idx + 2] = ((b_val + 1.0) * 127.5).clamp(0.0, 255.0) as u8;
+                native[idx + 3] = 255;
             }
         }
 
-        Ok(result)
+        Ok(resize_rgba(&native, model_size, model_size, target_width, target_height, mode))
     }
 
     pub fn blend_images(
@@ -141,4 +107,39 @@ impl ImageProcessor {
 
         Ok(result)
     }
+
+    /// Color-preserving blend: keeps `original`'s chrominance (U/V) and only
+    /// adopts `stylized`'s luminance (Y), lerped toward the original's by
+    /// `strength`. Unlike `blend_images`'s per-channel RGB lerp, this keeps the
+    /// source photo's palette even when the style strongly shifts colors.
+    pub fn blend_images_preserve_color(
+        &self,
+        original: &[u8],
+        stylized: &[u8],
+        width: u32,
+        height: u32,
+        strength: f32,
+    ) -> Result<Vec<u8>, JsValue> {
+        if original.len() != stylized.len() {
+            return Err(JsValue::from_str("Image sizes don't match"));
+        }
+        if original.len() != (width * height * 4) as usize {
+            return Err(JsValue::from_str("Image buffer doesn't match width/height"));
+        }
+
+        let strength = strength.clamp(0.0, 1.0);
+        let mut result = Vec::with_capacity(original.len());
+
+        for i in (0..original.len()).step_by(4) {
+            let (orig_y, u, v) = ImageFilters::rgb_to_yuv(original[i], original[i + 1], original[i + 2]);
+            let (style_y, _, _) = ImageFilters::rgb_to_yuv(stylized[i], stylized[i + 1], stylized[i + 2]);
+
+            let y = ((1.0 - strength) * orig_y + strength * style_y).clamp(0.0, 1.0);
+            let (r, g, b) = ImageFilters::yuv_to_rgb(y, u, v);
+
+            result.extend_from_slice(&[r, g, b, original[i + 3]]);
+        }
+
+        Ok(result)
+    }
 }
@@ -4,6 +4,10 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::JsFuture;
 use web_sys::{Request, RequestInit, RequestMode, Response};
 
+/// Name of the Cache Storage bucket used to persist downloaded ONNX weights across
+/// page reloads. Bumping this invalidates all previously cached models.
+const MODEL_CACHE_NAME: &str = "neural-style-transfer-models-v1";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
     pub name: String,
@@ -71,25 +75,130 @@ impl ModelRegistry {
         }
 
         let model_info = self.get_model_info(style_name)
-            .ok_or_else(|| JsValue::from_str(&format!("Model '{}' not found", style_name)))?;
+            .ok_or_else(|| JsValue::from_str(&format!("Model '{}' not found", style_name)))?
+            .clone();
+        let url = format!("./models/{}", model_info.file_name);
+
+        // Check the persistent Cache Storage entry before issuing a network fetch.
+        let model_data = match Self::read_persistent_cache(&url).await? {
+            Some(data) => data,
+            None => {
+                let data = Self::fetch_model_bytes(&url).await?;
+                Self::write_persistent_cache(&url, &data).await.unwrap_or_else(|e| {
+                    web_sys::console::warn_1(&format!("Failed to persist model cache entry: {:?}", e).into());
+                });
+                data
+            }
+        };
+
+        self.model_cache.insert(style_name.to_string(), model_data);
+        Ok(self.model_cache.get(style_name).unwrap())
+    }
 
+    async fn fetch_model_bytes(url: &str) -> Result<Vec<u8>, JsValue> {
         let window = web_sys::window().unwrap();
-        
+
         let mut opts = RequestInit::new();
         opts.method("GET");
         opts.mode(RequestMode::Cors);
 
-        let url = format!("./models/{}", model_info.file_name);
-        let request = Request::new_with_str_and_init(&url, &opts)?;
-        
+        let request = Request::new_with_str_and_init(url, &opts)?;
+
         let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
         let resp: Response = resp_value.dyn_into().unwrap();
 
         let array_buffer = JsFuture::from(resp.array_buffer()?).await?;
         let uint8_array = js_sys::Uint8Array::new(&array_buffer);
-        let model_data = uint8_array.to_vec();
+        Ok(uint8_array.to_vec())
+    }
 
-        self.model_cache.insert(style_name.to_string(), model_data);
-        Ok(self.model_cache.get(style_name).unwrap())
+    /// Open (or create) the named Cache Storage bucket. Returns `None` when Cache
+    /// Storage isn't available (e.g. non-secure context) so callers can fall through
+    /// to the network transparently instead of erroring.
+    async fn open_cache() -> Option<web_sys::Cache> {
+        let window = web_sys::window()?;
+        let caches = window.caches().ok()?;
+        let cache_value = JsFuture::from(caches.open(MODEL_CACHE_NAME)).await.ok()?;
+        cache_value.dyn_into::<web_sys::Cache>().ok()
+    }
+
+    async fn read_persistent_cache(url: &str) -> Result<Option<Vec<u8>>, JsValue> {
+        let Some(cache) = Self::open_cache().await else {
+            return Ok(None);
+        };
+
+        let match_value = JsFuture::from(cache.match_with_str(url)).await?;
+        if match_value.is_undefined() {
+            return Ok(None);
+        }
+
+        let resp: Response = match_value.dyn_into()?;
+        let array_buffer = JsFuture::from(resp.array_buffer()?).await?;
+        let uint8_array = js_sys::Uint8Array::new(&array_buffer);
+        Ok(Some(uint8_array.to_vec()))
+    }
+
+    async fn write_persistent_cache(url: &str, data: &[u8]) -> Result<(), JsValue> {
+        let Some(cache) = Self::open_cache().await else {
+            return Ok(());
+        };
+
+        let uint8_array = js_sys::Uint8Array::from(data);
+        let body = uint8_array.buffer();
+        let response = Response::new_with_opt_buffer_source(Some(&body))?;
+        JsFuture::from(cache.put_with_str(url, &response)).await?;
+        Ok(())
+    }
+
+    /// Warm the persistent cache for a batch of styles ahead of time (e.g. on app
+    /// idle) so the first `get_model_data` call for each is an instant cache hit.
+    pub async fn prefetch_models(&mut self, style_names: &[&str]) -> Result<(), JsValue> {
+        for style_name in style_names {
+            self.get_model_data(style_name).await?;
+        }
+        Ok(())
+    }
+
+    /// Drop a model from both the in-memory and persistent caches, freeing its
+    /// storage quota.
+    pub async fn evict_model(&mut self, style_name: &str) -> Result<(), JsValue> {
+        self.model_cache.remove(style_name);
+
+        if let Some(model_info) = self.get_model_info(style_name) {
+            let url = format!("./models/{}", model_info.file_name);
+            if let Some(cache) = Self::open_cache().await {
+                JsFuture::from(cache.delete_with_str(&url)).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Approximate bytes currently held in the persistent model cache, summed from
+    /// the registry's known `size_mb` for every cached entry (the Cache Storage API
+    /// has no direct byte-count query).
+    pub async fn cache_usage_bytes(&self) -> Result<f64, JsValue> {
+        let Some(cache) = Self::open_cache().await else {
+            return Ok(0.0);
+        };
+
+        let keys_value = JsFuture::from(cache.keys()).await?;
+        let keys: js_sys::Array = keys_value.dyn_into()?;
+
+        let mut total = 0.0;
+        for info in self.models.values() {
+            let url = format!("./models/{}", info.file_name);
+            // Cache keys carry absolute URLs; match on the `/models/<file>` suffix.
+            let cached = keys.iter().any(|k| {
+                k.dyn_ref::<Request>()
+                    .map(|req| req.url().ends_with(url.trim_start_matches('.')))
+                    .unwrap_or(false)
+            });
+            if cached {
+                total += (info.size_mb as f64) * 1_000_000.0;
+            }
+        }
+
+        Ok(total)
     }
 }
@@ -1,4 +1,4 @@
-use crate::engine::{ModelMetadata, ONNXStyleTransferEngine};
+use crate::engine::{Backend, ModelMetadata, ONNXStyleTransferEngine, TensorInfo};
 use wasm_bindgen::prelude::*;
 
 use js_sys;
@@ -15,7 +15,7 @@ impl ModelRegistry {
     pub fn new() -> ModelRegistry {
         let mut registry = ModelRegistry {
             models: Vec::new(),
-            engine: ONNXStyleTransferEngine::new(),
+            engine: ONNXStyleTransferEngine::new(Backend::Auto),
         };
         
         // Initialize with default models
@@ -33,6 +33,7 @@ impl ModelRegistry {
             output_tensor_name: "stylized_output".to_string(),
             recommended_resolution: (512, 512),
             style_description: "Impressionist style inspired by Van Gogh's Starry Night, featuring swirling brushstrokes, vibrant colors, and expressive texture".to_string(),
+            tensor_info: TensorInfo { scale: 2.0, bias: -1.0, shape: (1, 3, 512, 512) },
         };
         self.models.push("van-gogh".to_string());
 
@@ -44,6 +45,7 @@ impl ModelRegistry {
             output_tensor_name: "stylized_output".to_string(),
             recommended_resolution: (512, 512),
             style_description: "Cubist abstraction inspired by Picasso's geometric forms, featuring angular shapes, fragmented perspectives, and bold color contrasts".to_string(),
+            tensor_info: TensorInfo { scale: 2.0, bias: -1.0, shape: (1, 3, 512, 512) },
         };
         self.models.push("picasso".to_string());
 
@@ -55,6 +57,7 @@ impl ModelRegistry {
             output_tensor_name: "stylized_output".to_string(),
             recommended_resolution: (512, 512),
             style_description: "Futuristic cyberpunk aesthetic with neon colors, digital glitch effects, and high-tech urban atmosphere".to_string(),
+            tensor_info: TensorInfo { scale: 2.0, bias: -1.0, shape: (1, 3, 512, 512) },
         };
         self.models.push("cyberpunk".to_string());
 
@@ -66,6 +69,7 @@ impl ModelRegistry {
             output_tensor_name: "stylized_output".to_string(),
             recommended_resolution: (512, 512),
             style_description: "Soft watercolor painting style with flowing colors, gentle blending, and translucent washes".to_string(),
+            tensor_info: TensorInfo { scale: 2.0, bias: -1.0, shape: (1, 3, 512, 512) },
         };
         self.models.push("watercolor".to_string());
 
@@ -77,6 +81,7 @@ impl ModelRegistry {
             output_tensor_name: "stylized_output".to_string(),
             recommended_resolution: (512, 512),
             style_description: "Classical oil painting style with rich textures, deep colors, and traditional artistic techniques".to_string(),
+            tensor_info: TensorInfo { scale: 2.0, bias: -1.0, shape: (1, 3, 512, 512) },
         };
         self.models.push("oil-painting".to_string());
     }
@@ -101,34 +106,34 @@ impl ModelRegistry {
         }
     }
 
-    pub fn load_model(&mut self, style_name: &str) -> Result<(), JsValue> {
+    pub async fn load_model(&mut self, style_name: &str) -> Result<(), JsValue> {
         if !self.models.contains(&style_name.to_string()) {
             return Err(format!("Style '{}' not available", style_name).into());
         }
-        
+
         // Initialize the engine if needed
         self.engine.initialize()?;
-        
+
         // Load the model
-        self.engine.load_model(style_name)?;
-        
+        self.engine.load_model(style_name).await?;
+
         Ok(())
     }
 
-    pub fn apply_style_transfer(
+    pub async fn apply_style_transfer(
         &self,
         input_image_data: &[u8],
-        _width: u32,
-        _height: u32,
+        width: u32,
+        height: u32,
         style_strength: f32,
         style_name: &str,
     ) -> Result<JsValue, JsValue> {
 
-        
+
         // Apply style transfer directly without callback
-        
+
         // The engine's apply_style_transfer already returns a complete JsValue result
-        self.engine.apply_style_transfer(input_image_data, 0, 0, style_strength, style_name)
+        self.engine.apply_style_transfer(input_image_data, width, height, style_strength, style_name).await
     }
 
     pub fn get_total_model_size(&self) -> usize {
@@ -1,9 +1,30 @@
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::closure::Closure;
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{Request, RequestInit, RequestMode, Response};
+use web_sys::{Headers, IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode, ReadableStream, Request, RequestInit, RequestMode, Response};
 use js_sys::{ArrayBuffer, Uint8Array};
 use serde::{Serialize, Deserialize};
 use std::collections::HashMap;
+use wonnx::session::Session as WonnxSession;
+use wonnx::onnx::ModelProto;
+use protobuf::Message;
+use std::cell::RefCell;
+use crate::gpu::{preprocess as gpu_preprocess, GpuContext};
+
+/// IndexedDB database/object-store names backing the persistent model byte cache.
+/// Bumping `MODEL_DB_VERSION` forces `onupgradeneeded` to recreate the store.
+const MODEL_DB_NAME: &str = "onnx-style-transfer-models";
+const MODEL_DB_VERSION: u32 = 1;
+const MODEL_STORE_NAME: &str = "models";
+
+/// Resampling quality used by `resize_image_data`: `Nearest` is cheap integer
+/// sampling, `Bilinear` blends the four surrounding source texels for smoother
+/// results when scaling between display and model-native resolutions.
+#[derive(Clone, Copy, PartialEq)]
+enum ResampleFilter {
+    Nearest,
+    Bilinear,
+}
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ONNXModelMetadata {
@@ -16,6 +37,9 @@ pub struct ONNXModelMetadata {
     pub output_tensor_name: String,
     pub recommended_resolution: (u32, u32),
     pub style_description: String,
+    /// URL of a representative preview image for this style, for a UI to
+    /// display alongside `style_description`.
+    pub preview_image: String,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -27,23 +51,38 @@ pub struct StyleTransferResult {
     pub model_info: Option<String>,
 }
 
-#[wasm_bindgen]
-pub struct ONNXStyleTransferEngine {
+// `engine::ONNXStyleTransferEngine` (chunk3-3) is the real tract-onnx CPU /
+// wonnx GPU engine and already claims the unqualified wasm-bindgen export name;
+// this one keeps its own simulated-CPU-pixel-math fallback (kept intentionally,
+// see chunk1-1) under a distinct JS class name so both can be wired into the
+// crate at once without clobbering each other's generated glue.
+#[wasm_bindgen(js_name = "LegacyOnnxStyleTransferEngine")]
+pub struct LegacyOnnxStyleTransferEngine {
     models: HashMap<String, Vec<u8>>,
     model_metadata: HashMap<String, ONNXModelMetadata>,
     loaded_models: HashMap<String, bool>,
+    // WebGPU-backed wonnx sessions, built once per style the first time it's loaded.
+    // Absent (e.g. no GPU adapter) means `run_onnx_inference` falls back to the
+    // simulated CPU path below.
+    sessions: HashMap<String, WonnxSession>,
+    // Lazily negotiated on the first `use_gpu_preprocessing` call, then reused for
+    // every subsequent one. `&self` methods reach into this via `RefCell` since
+    // `apply_style_transfer` is otherwise a read-only, wasm_bindgen-exposed method.
+    gpu_context: RefCell<Option<GpuContext>>,
 }
 
 #[wasm_bindgen]
-impl ONNXStyleTransferEngine {
+impl LegacyOnnxStyleTransferEngine {
     #[wasm_bindgen(constructor)]
-    pub fn new() -> ONNXStyleTransferEngine {
+    pub fn new() -> LegacyOnnxStyleTransferEngine {
         console_error_panic_hook::set_once();
         
-        let mut engine = ONNXStyleTransferEngine {
+        let mut engine = LegacyOnnxStyleTransferEngine {
             models: HashMap::new(),
             model_metadata: HashMap::new(),
             loaded_models: HashMap::new(),
+            sessions: HashMap::new(),
+            gpu_context: RefCell::new(None),
         };
         
         engine.initialize_model_registry();
@@ -62,6 +101,7 @@ impl ONNXStyleTransferEngine {
             output_tensor_name: "output".to_string(),
             recommended_resolution: (256, 256),
             style_description: "Impressionist style inspired by Van Gogh's Starry Night with swirling brushstrokes".to_string(),
+            preview_image: "/previews/van_gogh.jpg".to_string(),
         };
         self.model_metadata.insert("van-gogh".to_string(), van_gogh_metadata);
 
@@ -76,6 +116,7 @@ impl ONNXStyleTransferEngine {
             output_tensor_name: "output".to_string(),
             recommended_resolution: (256, 256),
             style_description: "Cubist abstraction inspired by Picasso's geometric forms and bold colors".to_string(),
+            preview_image: "/previews/picasso.jpg".to_string(),
         };
         self.model_metadata.insert("picasso".to_string(), picasso_metadata);
 
@@ -90,6 +131,7 @@ impl ONNXStyleTransferEngine {
             output_tensor_name: "output".to_string(),
             recommended_resolution: (256, 256),
             style_description: "Futuristic cyberpunk aesthetic with neon colors and digital effects".to_string(),
+            preview_image: "/previews/cyberpunk.jpg".to_string(),
         };
         self.model_metadata.insert("cyberpunk".to_string(), cyberpunk_metadata);
 
@@ -104,6 +146,7 @@ impl ONNXStyleTransferEngine {
             output_tensor_name: "output".to_string(),
             recommended_resolution: (256, 256),
             style_description: "Soft watercolor painting style with flowing colors and gentle blending".to_string(),
+            preview_image: "/previews/watercolor.jpg".to_string(),
         };
         self.model_metadata.insert("watercolor".to_string(), watercolor_metadata);
 
@@ -118,6 +161,7 @@ impl ONNXStyleTransferEngine {
             output_tensor_name: "output".to_string(),
             recommended_resolution: (256, 256),
             style_description: "Classical oil painting style with rich textures and deep colors".to_string(),
+            preview_image: "/previews/oil_painting.jpg".to_string(),
         };
         self.model_metadata.insert("oil-painting".to_string(), oil_painting_metadata);
     }
@@ -137,7 +181,40 @@ impl ONNXStyleTransferEngine {
         }
     }
 
+    /// Typed (non-`JsValue`) metadata lookup for `ONNXModelRegistry::find_style_by_prompt`,
+    /// which needs plain `String` fields to match keywords against rather than
+    /// a serialized value.
+    pub(crate) fn all_model_metadata(&self) -> Vec<ONNXModelMetadata> {
+        self.model_metadata.values().cloned().collect()
+    }
+
+    /// Real total size of every style's weights currently resident in `models`
+    /// (i.e. already downloaded), not the full catalog's size.
+    pub fn get_total_loaded_bytes(&self) -> usize {
+        self.models.values().map(|bytes| bytes.len()).sum()
+    }
+
+    pub fn get_loaded_model_count(&self) -> usize {
+        self.loaded_models.values().filter(|&&loaded| loaded).count()
+    }
+
+    pub fn is_model_loaded(&self, style_name: &str) -> bool {
+        *self.loaded_models.get(style_name).unwrap_or(&false)
+    }
+
     pub async fn load_model(&mut self, style_name: &str) -> Result<(), JsValue> {
+        self.load_model_with_progress(style_name, None).await
+    }
+
+    /// Same as `load_model`, but calls `on_progress(bytes_received, bytes_total)`
+    /// (when given) as the network download streams in, so a UI can render a
+    /// download bar. `bytes_total` is `0` when the server didn't send a
+    /// `Content-Length`. Not called at all on an IndexedDB cache hit.
+    pub async fn load_model_with_progress(
+        &mut self,
+        style_name: &str,
+        on_progress: Option<js_sys::Function>,
+    ) -> Result<(), JsValue> {
         if !self.model_metadata.contains_key(style_name) {
             return Err(format!("Style '{}' not available", style_name).into());
         }
@@ -147,15 +224,59 @@ impl ONNXStyleTransferEngine {
         }
 
         let metadata = self.model_metadata.get(style_name).unwrap();
-        
-        // Download the ONNX model
-        let model_data = self.download_model(&metadata.url).await?;
-        
+
+        // Check the persistent IndexedDB cache (conditional-GET revalidated) before
+        // falling through to a full download.
+        let (model_data, etag, last_modified, is_fresh) = Self::get_model_bytes(&metadata.url, on_progress.as_ref()).await?;
+
         // Validate the model
         if !self.validate_onnx_model(&model_data) {
             return Err(format!("Invalid ONNX model for style '{}'", style_name).into());
         }
 
+        if is_fresh {
+            Self::write_cache_entry(&metadata.url, &model_data, etag.as_deref(), last_modified.as_deref())
+                .await
+                .unwrap_or_else(|e| {
+                    web_sys::console::warn_1(&format!("Failed to persist model cache entry: {:?}", e).into());
+                });
+        }
+
+        // Read the graph's actual input/output tensor names, shapes, and resolution
+        // from the protobuf rather than trusting the hardcoded registry entry.
+        match Self::parse_model_metadata(&model_data, style_name, &metadata.url, &metadata.style_description, &metadata.preview_image) {
+            Ok(parsed) => {
+                self.model_metadata.insert(style_name.to_string(), parsed);
+            }
+            Err(e) => {
+                web_sys::console::warn_1(
+                    &format!("Failed to parse ONNX metadata for '{}', keeping registry defaults: {:?}", style_name, e).into(),
+                );
+            }
+        }
+
+        // Build a real WebGPU-backed wonnx session from the ONNX protobuf bytes.
+        // If no GPU adapter is available (or the graph uses an op wonnx doesn't
+        // support yet), keep the bytes for the simulated CPU fallback path instead
+        // of failing the whole load.
+        match WonnxSession::from_bytes(&model_data).await {
+            Ok(session) => {
+                self.sessions.insert(style_name.to_string(), session);
+                web_sys::console::log_1(
+                    &format!("✅ ONNX model '{}' loaded with WebGPU (wonnx) backend", style_name).into(),
+                );
+            }
+            Err(e) => {
+                web_sys::console::warn_1(
+                    &format!(
+                        "wonnx session creation failed for '{}' ({:?}); using simulated CPU fallback",
+                        style_name, e
+                    )
+                    .into(),
+                );
+            }
+        }
+
         // Store the model
         self.models.insert(style_name.to_string(), model_data);
         self.loaded_models.insert(style_name.to_string(), true);
@@ -164,39 +285,386 @@ impl ONNXStyleTransferEngine {
         Ok(())
     }
 
-    async fn download_model(&self, url: &str) -> Result<Vec<u8>, JsValue> {
+    /// Resolve `url`'s model bytes, preferring the IndexedDB cache but always
+    /// revalidating it with a conditional GET (`If-None-Match`/`If-Modified-Since`)
+    /// so a changed model on the server invalidates the cache automatically.
+    /// Returns `(bytes, etag, last_modified, is_fresh)`, where `is_fresh` tells the
+    /// caller whether the bytes came off the network (and so need re-caching) or
+    /// are an unmodified (304) cache hit.
+    async fn get_model_bytes(url: &str, on_progress: Option<&js_sys::Function>) -> Result<(Vec<u8>, Option<String>, Option<String>, bool), JsValue> {
+        let cached = Self::read_cache_entry(url).await.unwrap_or(None);
+
+        let (if_none_match, if_modified_since) = cached
+            .as_ref()
+            .map(|(_, etag, last_modified)| (etag.clone(), last_modified.clone()))
+            .unwrap_or((None, None));
+
+        match Self::fetch_model_bytes(url, if_none_match.as_deref(), if_modified_since.as_deref(), on_progress).await? {
+            Some((bytes, etag, last_modified)) => Ok((bytes, etag, last_modified, true)),
+            None => {
+                // HTTP 304 Not Modified only happens in response to the conditional
+                // headers above, which are only sent when `cached` is `Some`.
+                let (bytes, etag, last_modified) = cached.expect("304 implies a cache entry was present");
+                Ok((bytes, etag, last_modified, false))
+            }
+        }
+    }
+
+    /// Plain (or conditional, when `if_none_match`/`if_modified_since` are given)
+    /// network fetch of the ONNX model bytes. Returns `None` only for an HTTP 304
+    /// response to a conditional request.
+    async fn fetch_model_bytes(
+        url: &str,
+        if_none_match: Option<&str>,
+        if_modified_since: Option<&str>,
+        on_progress: Option<&js_sys::Function>,
+    ) -> Result<Option<(Vec<u8>, Option<String>, Option<String>)>, JsValue> {
         let opts = RequestInit::new();
         opts.set_method("GET");
         opts.set_mode(RequestMode::Cors);
 
+        if if_none_match.is_some() || if_modified_since.is_some() {
+            let headers = Headers::new()?;
+            if let Some(etag) = if_none_match {
+                headers.append("If-None-Match", etag)?;
+            }
+            if let Some(last_modified) = if_modified_since {
+                headers.append("If-Modified-Since", last_modified)?;
+            }
+            opts.set_headers(&headers);
+        }
+
         let request = Request::new_with_str_and_init(url, &opts)?;
-        
+
         let window = web_sys::window().unwrap();
         let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
         let resp: Response = resp_value.dyn_into().unwrap();
 
+        if resp.status() == 304 {
+            return Ok(None);
+        }
         if !resp.ok() {
             return Err(format!("Failed to download model: HTTP {}", resp.status()).into());
         }
 
-        let array_buffer = JsFuture::from(resp.array_buffer()?).await?;
-        let array_buffer: ArrayBuffer = array_buffer.dyn_into().unwrap();
-        let uint8_array = Uint8Array::new(&array_buffer);
-        let mut model_data = vec![0u8; uint8_array.length() as usize];
-        uint8_array.copy_to(&mut model_data);
+        let etag = resp.headers().get("ETag").ok().flatten();
+        let last_modified = resp.headers().get("Last-Modified").ok().flatten();
+
+        let model_data = Self::read_body_with_progress(&resp, on_progress).await?;
+
+        Ok(Some((model_data, etag, last_modified)))
+    }
+
+    /// Reads `resp`'s body, calling `on_progress(bytes_received, bytes_total)`
+    /// after every chunk when given (`bytes_total` is `0` if the response has no
+    /// `Content-Length`). Falls back to buffering the whole body at once when the
+    /// response has no streaming `body()` (e.g. in test/mock fetch shims).
+    async fn read_body_with_progress(resp: &Response, on_progress: Option<&js_sys::Function>) -> Result<Vec<u8>, JsValue> {
+        let total: u32 = resp.headers().get("Content-Length").ok().flatten()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+
+        let Some(stream) = resp.body() else {
+            let array_buffer = JsFuture::from(resp.array_buffer()?).await?;
+            let array_buffer: ArrayBuffer = array_buffer.dyn_into().unwrap();
+            let uint8_array = Uint8Array::new(&array_buffer);
+            let mut bytes = vec![0u8; uint8_array.length() as usize];
+            uint8_array.copy_to(&mut bytes);
+            if let Some(cb) = on_progress {
+                let _ = cb.call2(&JsValue::NULL, &(bytes.len() as u32).into(), &total.into());
+            }
+            return Ok(bytes);
+        };
+
+        let reader: web_sys::ReadableStreamDefaultReader = stream.get_reader().unchecked_into();
+        let mut bytes: Vec<u8> = Vec::with_capacity(total as usize);
+
+        loop {
+            let result = JsFuture::from(reader.read()).await?;
+            let done = js_sys::Reflect::get(&result, &"done".into())?.as_bool().unwrap_or(true);
+            if done {
+                break;
+            }
+
+            let value = js_sys::Reflect::get(&result, &"value".into())?;
+            let chunk: Uint8Array = value.unchecked_into();
+            let offset = bytes.len();
+            bytes.resize(offset + chunk.length() as usize, 0);
+            chunk.copy_to(&mut bytes[offset..]);
+
+            if let Some(cb) = on_progress {
+                let _ = cb.call2(&JsValue::NULL, &(bytes.len() as u32).into(), &total.into());
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// Open (creating on first use) the IndexedDB database backing the model byte
+    /// cache.
+    async fn open_model_db() -> Result<IdbDatabase, JsValue> {
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("no global `window` exists"))?;
+        let idb_factory = window
+            .indexed_db()?
+            .ok_or_else(|| JsValue::from_str("IndexedDB is not available"))?;
+        let open_request = idb_factory.open_with_u32(MODEL_DB_NAME, MODEL_DB_VERSION)?;
+
+        let upgrade_request = open_request.clone();
+        let onupgradeneeded = Closure::once(Box::new(move |_evt: web_sys::Event| {
+            if let Ok(result) = upgrade_request.result() {
+                let db: IdbDatabase = result.unchecked_into();
+                if !db.object_store_names().contains(MODEL_STORE_NAME) {
+                    let _ = db.create_object_store(MODEL_STORE_NAME);
+                }
+            }
+        }) as Box<dyn FnOnce(web_sys::Event)>);
+        open_request.set_onupgradeneeded(Some(onupgradeneeded.as_ref().unchecked_ref()));
+        onupgradeneeded.forget();
+
+        let result = JsFuture::from(Self::idb_request_to_promise(&open_request)).await?;
+        Ok(result.unchecked_into())
+    }
+
+    /// Wrap a one-shot `IDBRequest`'s `onsuccess`/`onerror` callbacks in a Promise so
+    /// it can be `.await`ed like the rest of this file's fetch/Cache-API calls.
+    fn idb_request_to_promise(request: &IdbRequest) -> js_sys::Promise {
+        let success_request = request.clone();
+        let error_request = request.clone();
+        js_sys::Promise::new(&mut |resolve, reject| {
+            let onsuccess = Closure::once(Box::new(move |_evt: web_sys::Event| {
+                let _ = resolve.call1(&JsValue::NULL, &success_request.result().unwrap_or(JsValue::NULL));
+            }) as Box<dyn FnOnce(web_sys::Event)>);
+            let onerror = Closure::once(Box::new(move |_evt: web_sys::Event| {
+                let error = error_request.error().ok().flatten().map(JsValue::from).unwrap_or(JsValue::NULL);
+                let _ = reject.call1(&JsValue::NULL, &error);
+            }) as Box<dyn FnOnce(web_sys::Event)>);
+            request.set_onsuccess(Some(onsuccess.as_ref().unchecked_ref()));
+            request.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+            onsuccess.forget();
+            onerror.forget();
+        })
+    }
+
+    fn model_store(db: &IdbDatabase, mode: IdbTransactionMode) -> Result<IdbObjectStore, JsValue> {
+        let transaction = db.transaction_with_str_and_mode(MODEL_STORE_NAME, mode)?;
+        transaction.object_store(MODEL_STORE_NAME)
+    }
+
+    /// Read a cached `(bytes, etag, last_modified)` entry keyed by model URL.
+    /// Returns `Ok(None)` both when IndexedDB is unavailable and on a cache miss,
+    /// so callers can treat both the same way: fall through to the network.
+    async fn read_cache_entry(url: &str) -> Result<Option<(Vec<u8>, Option<String>, Option<String>)>, JsValue> {
+        let Ok(db) = Self::open_model_db().await else {
+            return Ok(None);
+        };
+        let store = Self::model_store(&db, IdbTransactionMode::Readonly)?;
+        let request = store.get(&JsValue::from_str(url))?;
+        let result = JsFuture::from(Self::idb_request_to_promise(&request)).await?;
+
+        if result.is_undefined() || result.is_null() {
+            return Ok(None);
+        }
+
+        let bytes_value = js_sys::Reflect::get(&result, &JsValue::from_str("bytes"))?;
+        let bytes: Uint8Array = bytes_value.dyn_into()?;
+        let etag = js_sys::Reflect::get(&result, &JsValue::from_str("etag")).ok().and_then(|v| v.as_string());
+        let last_modified = js_sys::Reflect::get(&result, &JsValue::from_str("last_modified")).ok().and_then(|v| v.as_string());
+
+        Ok(Some((bytes.to_vec(), etag, last_modified)))
+    }
+
+    async fn write_cache_entry(url: &str, bytes: &[u8], etag: Option<&str>, last_modified: Option<&str>) -> Result<(), JsValue> {
+        let db = Self::open_model_db().await?;
+        let store = Self::model_store(&db, IdbTransactionMode::Readwrite)?;
+
+        let entry = js_sys::Object::new();
+        js_sys::Reflect::set(&entry, &JsValue::from_str("bytes"), &Uint8Array::from(bytes))?;
+        if let Some(etag) = etag {
+            js_sys::Reflect::set(&entry, &JsValue::from_str("etag"), &JsValue::from_str(etag))?;
+        }
+        if let Some(last_modified) = last_modified {
+            js_sys::Reflect::set(&entry, &JsValue::from_str("last_modified"), &JsValue::from_str(last_modified))?;
+        }
+
+        let request = store.put_with_key(&entry, &JsValue::from_str(url))?;
+        JsFuture::from(Self::idb_request_to_promise(&request)).await?;
+        Ok(())
+    }
 
-        Ok(model_data)
+    async fn delete_cache_entry(url: &str) -> Result<(), JsValue> {
+        let db = Self::open_model_db().await?;
+        let store = Self::model_store(&db, IdbTransactionMode::Readwrite)?;
+        let request = store.delete(&JsValue::from_str(url))?;
+        JsFuture::from(Self::idb_request_to_promise(&request)).await?;
+        Ok(())
+    }
+
+    /// Drop a loaded style from the in-memory engine state and its persistent
+    /// IndexedDB cache entry, so the next `load_model()` call re-downloads it.
+    pub async fn evict_model(&mut self, style_name: &str) -> Result<(), JsValue> {
+        self.models.remove(style_name);
+        self.sessions.remove(style_name);
+        self.loaded_models.remove(style_name);
+
+        if let Some(metadata) = self.model_metadata.get(style_name) {
+            Self::delete_cache_entry(&metadata.url).await?;
+        }
+        Ok(())
+    }
+
+    /// Clear every cached model, both in-memory and in IndexedDB.
+    pub async fn clear_cache(&mut self) -> Result<(), JsValue> {
+        self.models.clear();
+        self.sessions.clear();
+        self.loaded_models.clear();
+
+        let db = Self::open_model_db().await?;
+        let store = Self::model_store(&db, IdbTransactionMode::Readwrite)?;
+        let request = store.clear()?;
+        JsFuture::from(Self::idb_request_to_promise(&request)).await?;
+        Ok(())
+    }
+
+    /// Total bytes currently held in the persistent IndexedDB model cache.
+    pub async fn cached_bytes(&self) -> Result<f64, JsValue> {
+        let db = Self::open_model_db().await?;
+        let store = Self::model_store(&db, IdbTransactionMode::Readonly)?;
+        let request = store.get_all()?;
+        let result = JsFuture::from(Self::idb_request_to_promise(&request)).await?;
+        let entries: js_sys::Array = result.dyn_into()?;
+
+        let mut total = 0.0;
+        for entry in entries.iter() {
+            if let Ok(bytes_value) = js_sys::Reflect::get(&entry, &JsValue::from_str("bytes")) {
+                if let Ok(bytes) = bytes_value.dyn_into::<Uint8Array>() {
+                    total += bytes.length() as f64;
+                }
+            }
+        }
+        Ok(total)
     }
 
     fn validate_onnx_model(&self, model_data: &[u8]) -> bool {
-        // Basic ONNX model validation
-        if model_data.len() < 100 {
-            return false;
+        // Confirm the bytes actually decode as an ONNX ModelProto and describe
+        // exactly one image-shaped input and output, rather than just checking size.
+        match ModelProto::parse_from_bytes(model_data) {
+            Ok(model) => {
+                let graph = model.get_graph();
+                graph.get_input().len() == 1
+                    && graph.get_output().len() == 1
+                    && Self::value_info_image_dims(&graph.get_input()[0]).is_some()
+                    && Self::value_info_image_dims(&graph.get_output()[0]).is_some()
+            }
+            Err(_) => false,
         }
-        
-        // Check for ONNX protobuf magic bytes
-        // ONNX models typically start with specific protobuf headers
-        model_data.len() > 1000 // Simplified validation for now
+    }
+
+    /// Extract `(n, c, h, w)` from a `ValueInfoProto`'s tensor type, if it describes
+    /// a 4D image-shaped tensor.
+    fn value_info_image_dims(value_info: &wonnx::onnx::ValueInfoProto) -> Option<(i64, i64, i64, i64)> {
+        let tensor_type = value_info.get_field_type().get_tensor_type();
+        let dims = tensor_type.get_shape().get_dim();
+        if dims.len() != 4 {
+            return None;
+        }
+
+        let dim_value = |i: usize| -> i64 {
+            let d = &dims[i];
+            if d.has_dim_value() { d.get_dim_value() } else { -1 }
+        };
+
+        Some((dim_value(0), dim_value(1), dim_value(2), dim_value(3)))
+    }
+
+    /// Build an `ONNXModelMetadata` by reading the graph's input/output
+    /// `ValueInfoProto` entries straight from the protobuf instead of a hardcoded
+    /// registry: tensor names, shapes, and the recommended resolution all come from
+    /// the model itself.
+    fn parse_model_metadata(
+        model_data: &[u8],
+        name: &str,
+        url: &str,
+        style_description: &str,
+        preview_image: &str,
+    ) -> Result<ONNXModelMetadata, JsValue> {
+        let model = ModelProto::parse_from_bytes(model_data)
+            .map_err(|e| JsValue::from_str(&format!("Failed to parse ONNX protobuf: {}", e)))?;
+        let graph = model.get_graph();
+
+        let input = graph.get_input().get(0)
+            .ok_or_else(|| JsValue::from_str("ONNX graph has no inputs"))?;
+        let output = graph.get_output().get(0)
+            .ok_or_else(|| JsValue::from_str("ONNX graph has no outputs"))?;
+
+        let (_, _, h, w) = Self::value_info_image_dims(input)
+            .ok_or_else(|| JsValue::from_str("ONNX input is not a 4D image tensor"))?;
+        let (_, _, oh, ow) = Self::value_info_image_dims(output)
+            .ok_or_else(|| JsValue::from_str("ONNX output is not a 4D image tensor"))?;
+
+        let input_shape = vec![1, 3, h.max(1), w.max(1)];
+        let output_shape = vec![1, 3, oh.max(1), ow.max(1)];
+
+        Ok(ONNXModelMetadata {
+            name: name.to_string(),
+            url: url.to_string(),
+            size_bytes: model_data.len(),
+            input_shape,
+            output_shape,
+            input_tensor_name: input.get_name().to_string(),
+            output_tensor_name: output.get_name().to_string(),
+            recommended_resolution: (w.max(1) as u32, h.max(1) as u32),
+            style_description: style_description.to_string(),
+            preview_image: preview_image.to_string(),
+        })
+    }
+
+    /// Register an arbitrary style-transfer `.onnx` file by URL so callers aren't
+    /// limited to the van-gogh/picasso/etc. built-ins. The model is downloaded and
+    /// its metadata parsed the next time `load_model(name)` is called.
+    pub fn register_custom_model(&mut self, name: &str, model_url: &str) -> Result<(), JsValue> {
+        self.model_metadata.insert(
+            name.to_string(),
+            ONNXModelMetadata {
+                name: name.to_string(),
+                url: model_url.to_string(),
+                size_bytes: 0,
+                input_shape: vec![1, 3, 256, 256],
+                output_shape: vec![1, 3, 256, 256],
+                input_tensor_name: "input".to_string(),
+                output_tensor_name: "output".to_string(),
+                recommended_resolution: (256, 256),
+                style_description: format!("Custom style transfer model: {}", name),
+                preview_image: String::new(),
+            },
+        );
+        self.loaded_models.insert(name.to_string(), false);
+        Ok(())
+    }
+
+    /// Register and immediately load a custom model from bytes already in memory
+    /// (e.g. a user-selected file), bypassing the fetch step entirely.
+    pub async fn load_model_from_bytes(&mut self, name: &str, bytes: Vec<u8>) -> Result<(), JsValue> {
+        if !self.validate_onnx_model(&bytes) {
+            return Err(format!("'{}' is not a valid single-input/single-output image ONNX model", name).into());
+        }
+
+        let parsed = Self::parse_model_metadata(&bytes, name, "", &format!("Custom style transfer model: {}", name), "")?;
+        self.model_metadata.insert(name.to_string(), parsed);
+
+        match WonnxSession::from_bytes(&bytes).await {
+            Ok(session) => {
+                self.sessions.insert(name.to_string(), session);
+            }
+            Err(e) => {
+                web_sys::console::warn_1(
+                    &format!("wonnx session creation failed for custom model '{}' ({:?}); using simulated CPU fallback", name, e).into(),
+                );
+            }
+        }
+
+        self.models.insert(name.to_string(), bytes);
+        self.loaded_models.insert(name.to_string(), true);
+        Ok(())
     }
 
     pub async fn apply_style_transfer(
@@ -206,6 +674,7 @@ impl ONNXStyleTransferEngine {
         height: u32,
         style_strength: f32,
         style_name: &str,
+        use_gpu_preprocessing: bool,
     ) -> Result<JsValue, JsValue> {
         let start_time = js_sys::Date::now();
 
@@ -216,17 +685,51 @@ impl ONNXStyleTransferEngine {
         let model_data = self.models.get(style_name).unwrap();
         let metadata = self.model_metadata.get(style_name).unwrap();
 
-        // Preprocess image to tensor format
-        let input_tensor = self.preprocess_image(input_image_data, width, height, &metadata.input_shape)?;
+        let gpu_ctx = if use_gpu_preprocessing {
+            self.ensure_gpu_context().await
+        } else {
+            None
+        };
 
-        // Run ONNX inference (simplified for now - would use actual ONNX runtime)
+        let (target_height, target_width) = Self::resolve_spatial_dims(
+            &metadata.input_shape,
+            metadata.recommended_resolution,
+            width,
+            height,
+        )?;
+
+        // Preprocess image to tensor format: GPU-resident resize/normalize/pack when
+        // a device was negotiated, falling back to the CPU path otherwise.
+        let input_tensor = if let Some(ctx) = gpu_ctx.as_ref() {
+            gpu_preprocess::preprocess_gpu(ctx, input_image_data, width, height, target_width, target_height).await?
+        } else {
+            self.preprocess_image(input_image_data, width, height, metadata)?
+        };
+
+        // Run ONNX inference: real WebGPU inference through wonnx when a session was
+        // built for this style, simulated CPU fallback otherwise.
         let output_tensor = self.run_onnx_inference(&input_tensor, model_data, metadata).await?;
 
-        // Postprocess tensor back to image format
-        let output_image = self.postprocess_tensor(&output_tensor, width, height)?;
+        // Validate the tensor actually matches the declared output shape before
+        // trying to reinterpret it as an image.
+        let (out_h, out_w) = Self::resolve_spatial_dims(&metadata.output_shape, metadata.recommended_resolution, width, height)?;
+        if output_tensor.len() != (out_h * out_w * 3) as usize {
+            return Err(JsValue::from_str(&format!(
+                "inference output length {} doesn't match declared shape {:?}",
+                output_tensor.len(),
+                metadata.output_shape
+            )));
+        }
 
-        // Apply style strength blending
-        let final_output = self.blend_with_original(input_image_data, &output_image, style_strength);
+        // Postprocess tensor back to image format, keeping the tensor GPU-resident
+        // through the denormalize/blend step when the GPU path was taken above.
+        let final_output = if let Some(ctx) = gpu_ctx.as_ref() {
+            let original_resized = self.resize_rgba_nearest(input_image_data, width, height, out_w, out_h);
+            gpu_preprocess::postprocess_gpu(ctx, &output_tensor, &original_resized, out_w, out_h, style_strength).await?
+        } else {
+            let output_image = self.postprocess_tensor(&output_tensor, width, height, metadata)?;
+            self.blend_with_original(input_image_data, &output_image, style_strength)
+        };
 
         let end_time = js_sys::Date::now();
         let processing_time = end_time - start_time;
@@ -243,10 +746,82 @@ impl ONNXStyleTransferEngine {
             .map_err(|e| format!("Serialization failed: {}", e).into())
     }
 
-    fn preprocess_image(&self, image_data: &[u8], width: u32, height: u32, target_shape: &[i64]) -> Result<Vec<f32>, JsValue> {
-        let target_width = target_shape[3] as u32;
-        let target_height = target_shape[2] as u32;
-        
+    /// Negotiate (once) and cache a `GpuContext` for the GPU preprocessing path.
+    /// Returns `None` when no adapter/device could be acquired so callers fall back
+    /// to the CPU path transparently.
+    async fn ensure_gpu_context(&self) -> Option<std::cell::Ref<'_, GpuContext>> {
+        if self.gpu_context.borrow().is_none() {
+            let ctx = GpuContext::new().await.ok();
+            *self.gpu_context.borrow_mut() = ctx;
+        }
+
+        std::cell::Ref::filter_map(self.gpu_context.borrow(), |ctx| {
+            ctx.as_ref().filter(|c| c.is_available())
+        })
+        .ok()
+    }
+
+    /// Nearest-neighbor resize used to bring the original RGBA image to the
+    /// inference output resolution before the GPU postprocess blend (cheap enough
+    /// not to need its own shader).
+    fn resize_rgba_nearest(&self, data: &[u8], src_w: u32, src_h: u32, dst_w: u32, dst_h: u32) -> Vec<u8> {
+        let mut out = vec![0u8; (dst_w * dst_h * 4) as usize];
+        for y in 0..dst_h {
+            for x in 0..dst_w {
+                let src_x = (x * src_w / dst_w).min(src_w - 1);
+                let src_y = (y * src_h / dst_h).min(src_h - 1);
+                let src_idx = ((src_y * src_w + src_x) * 4) as usize;
+                let dst_idx = ((y * dst_w + x) * 4) as usize;
+                out[dst_idx..dst_idx + 4].copy_from_slice(&data[src_idx..src_idx + 4]);
+            }
+        }
+        out
+    }
+
+    /// Resolve a declared NCHW `[n, c, h, w]` shape's spatial dims to concrete
+    /// pixel sizes: a static (non-negative) dim is used as-is, a dynamic `-1` dim
+    /// falls back to the caller-provided width/height, clamped to
+    /// `recommended_resolution` when the model declares one.
+    fn resolve_spatial_dims(
+        shape: &[i64],
+        recommended_resolution: (u32, u32),
+        fallback_width: u32,
+        fallback_height: u32,
+    ) -> Result<(u32, u32), JsValue> {
+        if shape.len() != 4 {
+            return Err(JsValue::from_str(&format!("expected a 4D NCHW shape, got {:?}", shape)));
+        }
+
+        let (rec_w, rec_h) = recommended_resolution;
+        let resolve = |dim: i64, fallback: u32, recommended: u32| -> u32 {
+            if dim > 0 {
+                dim as u32
+            } else if recommended > 0 {
+                fallback.min(recommended)
+            } else {
+                fallback
+            }
+        };
+
+        let height = resolve(shape[2], fallback_height, rec_h);
+        let width = resolve(shape[3], fallback_width, rec_w);
+        Ok((height, width))
+    }
+
+    fn preprocess_image(
+        &self,
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        metadata: &ONNXModelMetadata,
+    ) -> Result<Vec<f32>, JsValue> {
+        let (target_height, target_width) = Self::resolve_spatial_dims(
+            &metadata.input_shape,
+            metadata.recommended_resolution,
+            width,
+            height,
+        )?;
+
         // Convert RGBA to RGB and normalize to [0,1]
         let mut rgb_data = Vec::new();
         for chunk in image_data.chunks(4) {
@@ -257,8 +832,7 @@ impl ONNXStyleTransferEngine {
             }
         }
 
-        // Simple resize (bilinear interpolation would be better)
-        let resized_data = self.resize_image_data(&rgb_data, width, height, target_width, target_height);
+        let resized_data = self.resize_image_data(&rgb_data, width, height, target_width, target_height, ResampleFilter::Bilinear);
 
         // Convert to CHW format (Channel, Height, Width)
         let mut tensor_data = vec![0.0f32; (target_width * target_height * 3) as usize];
@@ -280,17 +854,24 @@ impl ONNXStyleTransferEngine {
         Ok(tensor_data)
     }
 
-    fn resize_image_data(&self, data: &[f32], src_w: u32, src_h: u32, dst_w: u32, dst_h: u32) -> Vec<f32> {
+    fn resize_image_data(&self, data: &[f32], src_w: u32, src_h: u32, dst_w: u32, dst_h: u32, filter: ResampleFilter) -> Vec<f32> {
+        match filter {
+            ResampleFilter::Nearest => Self::resize_nearest(data, src_w, src_h, dst_w, dst_h),
+            ResampleFilter::Bilinear => Self::resize_bilinear(data, src_w, src_h, dst_w, dst_h),
+        }
+    }
+
+    fn resize_nearest(data: &[f32], src_w: u32, src_h: u32, dst_w: u32, dst_h: u32) -> Vec<f32> {
         let mut result = vec![0.0f32; (dst_w * dst_h * 3) as usize];
-        
+
         for y in 0..dst_h {
             for x in 0..dst_w {
                 let src_x = (x as f32 * src_w as f32 / dst_w as f32) as u32;
                 let src_y = (y as f32 * src_h as f32 / dst_h as f32) as u32;
-                
+
                 let src_idx = ((src_y * src_w + src_x) * 3) as usize;
                 let dst_idx = ((y * dst_w + x) * 3) as usize;
-                
+
                 if src_idx + 2 < data.len() && dst_idx + 2 < result.len() {
                     result[dst_idx] = data[src_idx];         // R
                     result[dst_idx + 1] = data[src_idx + 1]; // G
@@ -298,16 +879,65 @@ impl ONNXStyleTransferEngine {
                 }
             }
         }
-        
+
+        result
+    }
+
+    /// Bilinear resample: sample at each destination pixel's source-space center
+    /// (`(x + 0.5) * src/dst - 0.5`), blend the four surrounding source texels by
+    /// their fractional-position weights, and clamp indices at the edges rather
+    /// than leaving out-of-range destination pixels black.
+    fn resize_bilinear(data: &[f32], src_w: u32, src_h: u32, dst_w: u32, dst_h: u32) -> Vec<f32> {
+        let mut result = vec![0.0f32; (dst_w * dst_h * 3) as usize];
+        let max_x = src_w.saturating_sub(1);
+        let max_y = src_h.saturating_sub(1);
+
+        let pixel = |x: u32, y: u32, c: usize| -> f32 {
+            data[((y * src_w + x) * 3) as usize + c]
+        };
+
+        for y in 0..dst_h {
+            let sy = (y as f32 + 0.5) * src_h as f32 / dst_h as f32 - 0.5;
+            let y0 = sy.floor().max(0.0) as u32;
+            let y0 = y0.min(max_y);
+            let y1 = (y0 + 1).min(max_y);
+            let fy = (sy - y0 as f32).clamp(0.0, 1.0);
+
+            for x in 0..dst_w {
+                let sx = (x as f32 + 0.5) * src_w as f32 / dst_w as f32 - 0.5;
+                let x0 = sx.floor().max(0.0) as u32;
+                let x0 = x0.min(max_x);
+                let x1 = (x0 + 1).min(max_x);
+                let fx = (sx - x0 as f32).clamp(0.0, 1.0);
+
+                let dst_idx = ((y * dst_w + x) * 3) as usize;
+                for c in 0..3 {
+                    let p00 = pixel(x0, y0, c);
+                    let p10 = pixel(x1, y0, c);
+                    let p01 = pixel(x0, y1, c);
+                    let p11 = pixel(x1, y1, c);
+                    result[dst_idx + c] = (1.0 - fx) * (1.0 - fy) * p00
+                        + fx * (1.0 - fy) * p10
+                        + (1.0 - fx) * fy * p01
+                        + fx * fy * p11;
+                }
+            }
+        }
+
         result
     }
 
     async fn run_onnx_inference(&self, input_tensor: &[f32], _model_data: &[u8], metadata: &ONNXModelMetadata) -> Result<Vec<f32>, JsValue> {
-        // For now, simulate neural style transfer with advanced image processing
-        // In a full implementation, this would use actual ONNX Runtime
-        
-        web_sys::console::log_1(&format!("🧠 Running ONNX inference for {} model", metadata.name).into());
-        
+        if let Some(session) = self.sessions.get(&metadata.name) {
+            web_sys::console::log_1(&format!("🧠 Running wonnx/WebGPU inference for {} model", metadata.name).into());
+            return self.run_wonnx_inference(session, input_tensor, metadata).await;
+        }
+
+        // No GPU session for this style (no adapter, or the graph uses an op wonnx
+        // doesn't support): simulate neural style transfer with hand-tuned image
+        // processing so the pipeline still produces a plausible styled result.
+        web_sys::console::log_1(&format!("🧠 Running simulated inference for {} model (no GPU session)", metadata.name).into());
+
         let mut output = input_tensor.to_vec();
         let hw_size = (metadata.input_shape[2] * metadata.input_shape[3]) as usize;
         
@@ -375,11 +1005,57 @@ impl ONNXStyleTransferEngine {
         Ok(output)
     }
 
-    fn postprocess_tensor(&self, tensor_data: &[f32], target_width: u32, target_height: u32) -> Result<Vec<u8>, JsValue> {
-        let tensor_width = 256u32; // From model metadata
-        let tensor_height = 256u32;
+    /// Run the parsed ONNX graph's compute ops (Conv, InstanceNormalization, Relu,
+    /// Add, Upsample/ConvTranspose, Tanh) through wonnx on the browser's WebGPU
+    /// device, binding `input_tensor` under `metadata.input_tensor_name` and reading
+    /// the result back from `metadata.output_tensor_name`.
+    async fn run_wonnx_inference(
+        &self,
+        session: &WonnxSession,
+        input_tensor: &[f32],
+        metadata: &ONNXModelMetadata,
+    ) -> Result<Vec<f32>, JsValue> {
+        let mut outputs = session
+            .run(vec![(metadata.input_tensor_name.clone(), input_tensor.to_vec())])
+            .await
+            .map_err(|e| JsValue::from_str(&format!("wonnx inference failed for '{}': {}", metadata.name, e)))?;
+
+        outputs
+            .remove(&metadata.output_tensor_name)
+            .ok_or_else(|| {
+                JsValue::from_str(&format!(
+                    "wonnx output tensor '{}' missing for model '{}'",
+                    metadata.output_tensor_name, metadata.name
+                ))
+            })
+    }
+
+    fn postprocess_tensor(
+        &self,
+        tensor_data: &[f32],
+        target_width: u32,
+        target_height: u32,
+        metadata: &ONNXModelMetadata,
+    ) -> Result<Vec<u8>, JsValue> {
+        // Read the tensor's spatial dims straight from the declared output shape
+        // instead of assuming 256x256; a `-1`/dynamic dim falls back to the
+        // caller-provided target size clamped to the model's recommended resolution.
+        let (tensor_height, tensor_width) = Self::resolve_spatial_dims(
+            &metadata.output_shape,
+            metadata.recommended_resolution,
+            target_width,
+            target_height,
+        )?;
         let hw_size = (tensor_width * tensor_height) as usize;
-        
+
+        if tensor_data.len() < hw_size * 3 {
+            return Err(JsValue::from_str(&format!(
+                "output tensor length {} doesn't match declared shape {:?}",
+                tensor_data.len(),
+                metadata.output_shape
+            )));
+        }
+
         // Convert CHW back to HWC format
         let mut rgb_data = vec![0.0f32; (tensor_width * tensor_height * 3) as usize];
         
@@ -397,7 +1073,7 @@ impl ONNXStyleTransferEngine {
         }
         
         // Resize back to target dimensions
-        let resized_rgb = self.resize_image_data(&rgb_data, tensor_width, tensor_height, target_width, target_height);
+        let resized_rgb = self.resize_image_data(&rgb_data, tensor_width, tensor_height, target_width, target_height, ResampleFilter::Bilinear);
         
         // Convert back to RGBA bytes
         let mut rgba_data = Vec::new();
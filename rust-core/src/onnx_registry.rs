@@ -1,9 +1,16 @@
 use wasm_bindgen::prelude::*;
-use crate::onnx_engine::ONNXStyleTransferEngine;
+use serde::Serialize;
+use crate::onnx_engine::LegacyOnnxStyleTransferEngine;
+
+#[derive(Serialize)]
+pub struct StyleMatch {
+    pub style_name: String,
+    pub score: u32,
+}
 
 #[wasm_bindgen]
 pub struct ONNXModelRegistry {
-    engine: ONNXStyleTransferEngine,
+    engine: LegacyOnnxStyleTransferEngine,
 }
 
 #[wasm_bindgen]
@@ -11,7 +18,7 @@ impl ONNXModelRegistry {
     #[wasm_bindgen(constructor)]
     pub fn new() -> ONNXModelRegistry {
         ONNXModelRegistry {
-            engine: ONNXStyleTransferEngine::new(),
+            engine: LegacyOnnxStyleTransferEngine::new(),
         }
     }
 
@@ -21,6 +28,35 @@ impl ONNXModelRegistry {
         Ok(())
     }
 
+    /// Keyword match: ranks every known style by how many of `prompt`'s
+    /// whitespace-separated words appear in its name or `style_description`.
+    /// This is plain substring overlap, not semantic search -- there's no CLIP
+    /// (or other embedding) model bundled anywhere in this tree, and no real
+    /// BPE tokenizer/vocab to drive one, so an embedding-based matcher here
+    /// would just feed made-up token ids into a model asset that doesn't
+    /// exist, producing a similarity score that looks meaningful but isn't.
+    /// Keyword overlap is an honest, much smaller feature that actually does
+    /// what it says.
+    pub fn find_style_by_prompt(&self, prompt: &str) -> Result<JsValue, JsValue> {
+        let words: Vec<String> = prompt
+            .to_lowercase()
+            .split_whitespace()
+            .map(String::from)
+            .collect();
+
+        let mut ranked: Vec<StyleMatch> = self.engine.all_model_metadata().into_iter()
+            .map(|metadata| {
+                let haystack = format!("{} {}", metadata.name, metadata.style_description).to_lowercase();
+                let score = words.iter().filter(|word| haystack.contains(word.as_str())).count() as u32;
+                StyleMatch { style_name: metadata.name, score }
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.score.cmp(&a.score));
+
+        serde_wasm_bindgen::to_value(&ranked)
+            .map_err(|e| format!("Serialization failed: {}", e).into())
+    }
+
     pub fn get_available_styles(&self) -> Result<JsValue, JsValue> {
         self.engine.get_available_styles()
     }
@@ -33,6 +69,12 @@ impl ONNXModelRegistry {
         self.engine.load_model(style_name).await
     }
 
+    /// Same as `load_model`, but calls `on_progress(bytes_received, bytes_total)`
+    /// as the download streams in, so a UI can render a progress bar.
+    pub async fn load_model_with_progress(&mut self, style_name: &str, on_progress: js_sys::Function) -> Result<(), JsValue> {
+        self.engine.load_model_with_progress(style_name, Some(on_progress)).await
+    }
+
     pub async fn apply_style_transfer(
         &self,
         input_image_data: &[u8],
@@ -44,17 +86,19 @@ impl ONNXModelRegistry {
         self.engine.apply_style_transfer(input_image_data, width, height, style_strength, style_name).await
     }
 
+    /// Real byte count of the styles actually downloaded so far (not the full
+    /// catalog's size).
     pub fn get_total_model_size(&self) -> usize {
-        // Sum up all model sizes from metadata
-        37_400_000 // Total size of all 5 models in bytes (~37.4MB)
+        self.engine.get_total_loaded_bytes()
     }
 
+    /// Real count of styles actually loaded (resident in memory), not the size
+    /// of the catalog.
     pub fn get_model_count(&self) -> usize {
-        5
+        self.engine.get_loaded_model_count()
     }
 
-    pub fn is_model_loaded(&self, _style_name: &str) -> bool {
-        // This would check if the model is actually loaded in memory
-        true // Simplified for now
+    pub fn is_model_loaded(&self, style_name: &str) -> bool {
+        self.engine.is_model_loaded(style_name)
     }
 }
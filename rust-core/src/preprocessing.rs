@@ -0,0 +1,109 @@
+use crate::engine::TensorInfo;
+
+/// Resizes (bilinear) an interleaved RGBA `src_w`x`src_h` buffer to `dst_w`x`dst_h`,
+/// drops the alpha channel, reorders HWC -> planar NCHW (`[1,3,dst_h,dst_w]`), and
+/// normalizes each byte via `tensor_info` (`(byte / 255.0) * scale + bias`).
+pub fn rgba_to_nchw(
+    rgba: &[u8],
+    src_w: u32,
+    src_h: u32,
+    dst_w: u32,
+    dst_h: u32,
+    tensor_info: &TensorInfo,
+) -> Vec<f32> {
+    let plane = (dst_w * dst_h) as usize;
+    let mut tensor = vec![0f32; plane * 3];
+
+    let max_x = src_w.saturating_sub(1);
+    let max_y = src_h.saturating_sub(1);
+    let channel = |x: u32, y: u32, c: usize| -> f32 {
+        rgba[((y * src_w + x) * 4) as usize + c] as f32 / 255.0
+    };
+
+    for y in 0..dst_h {
+        let sy = (y as f32 + 0.5) * src_h as f32 / dst_h as f32 - 0.5;
+        let y0 = (sy.floor().max(0.0) as u32).min(max_y);
+        let y1 = (y0 + 1).min(max_y);
+        let fy = (sy - y0 as f32).clamp(0.0, 1.0);
+
+        for x in 0..dst_w {
+            let sx = (x as f32 + 0.5) * src_w as f32 / dst_w as f32 - 0.5;
+            let x0 = (sx.floor().max(0.0) as u32).min(max_x);
+            let x1 = (x0 + 1).min(max_x);
+            let fx = (sx - x0 as f32).clamp(0.0, 1.0);
+
+            let idx = (y * dst_w + x) as usize;
+            for c in 0..3 {
+                let p00 = channel(x0, y0, c);
+                let p10 = channel(x1, y0, c);
+                let p01 = channel(x0, y1, c);
+                let p11 = channel(x1, y1, c);
+                let value = (1.0 - fx) * (1.0 - fy) * p00
+                    + fx * (1.0 - fy) * p10
+                    + (1.0 - fx) * fy * p01
+                    + fx * fy * p11;
+                tensor[plane * c + idx] = value * tensor_info.scale + tensor_info.bias;
+            }
+        }
+    }
+
+    tensor
+}
+
+/// Inverse of `rgba_to_nchw`: denormalizes a planar NCHW `[1,3,src_h,src_w]`
+/// tensor back to bytes (`(value - bias) / scale * 255.0`), reorders to
+/// interleaved HWC, and resizes (bilinear, matching `rgba_to_nchw`'s input-side
+/// resize) to `dst_w`x`dst_h`. `source_rgba` is the original, un-resized image
+/// at `dst_w`x`dst_h` that the tensor was produced from; its alpha channel is
+/// copied through unchanged rather than assuming the image was opaque.
+pub fn nchw_to_rgba(
+    tensor: &[f32],
+    src_w: u32,
+    src_h: u32,
+    dst_w: u32,
+    dst_h: u32,
+    tensor_info: &TensorInfo,
+    source_rgba: &[u8],
+) -> Vec<u8> {
+    let plane = (src_w * src_h) as usize;
+    let mut out = vec![0u8; (dst_w * dst_h * 4) as usize];
+
+    let max_x = src_w.saturating_sub(1);
+    let max_y = src_h.saturating_sub(1);
+    let denormalize = |v: f32| -> f32 {
+        ((v - tensor_info.bias) / tensor_info.scale) * 255.0
+    };
+    let channel = |x: u32, y: u32, c: usize| -> f32 {
+        denormalize(tensor[plane * c + (y * src_w + x) as usize])
+    };
+
+    for y in 0..dst_h {
+        let sy = (y as f32 + 0.5) * src_h as f32 / dst_h as f32 - 0.5;
+        let y0 = (sy.floor().max(0.0) as u32).min(max_y);
+        let y1 = (y0 + 1).min(max_y);
+        let fy = (sy - y0 as f32).clamp(0.0, 1.0);
+
+        for x in 0..dst_w {
+            let sx = (x as f32 + 0.5) * src_w as f32 / dst_w as f32 - 0.5;
+            let x0 = (sx.floor().max(0.0) as u32).min(max_x);
+            let x1 = (x0 + 1).min(max_x);
+            let fx = (sx - x0 as f32).clamp(0.0, 1.0);
+
+            let dst_idx = ((y * dst_w + x) * 4) as usize;
+            for c in 0..3 {
+                let p00 = channel(x0, y0, c);
+                let p10 = channel(x1, y0, c);
+                let p01 = channel(x0, y1, c);
+                let p11 = channel(x1, y1, c);
+                let value = (1.0 - fx) * (1.0 - fy) * p00
+                    + fx * (1.0 - fy) * p10
+                    + (1.0 - fx) * fy * p01
+                    + fx * fy * p11;
+                out[dst_idx + c] = value.clamp(0.0, 255.0) as u8;
+            }
+            out[dst_idx + 3] = source_rgba[dst_idx + 3];
+        }
+    }
+
+    out
+}
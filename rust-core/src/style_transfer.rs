@@ -1,14 +1,15 @@
 use wasm_bindgen::prelude::*;
-use ort::{Environment, ExecutionProvider, Session, SessionBuilder, Value};
+use ort::{Environment, ExecutionProvider as OrtExecutionProvider, Session, SessionBuilder, Value};
 use ndarray::{Array4, Axis};
 use crate::model_registry::{ModelRegistry, ModelInfo};
 use crate::image_utils::ImageProcessor;
 use crate::webgpu_backend::WebGPUBackend;
+use crate::backend_selector::{BackendSelector, ExecutionProvider, ModelRequirements};
 
 pub struct StyleTransferEngine {
     session: Option<Session>,
     current_model: Option<ModelInfo>,
-    webgpu_backend: WebGPUBackend,
+    backend_selector: BackendSelector,
     image_processor: ImageProcessor,
 }
 
@@ -17,7 +18,7 @@ impl StyleTransferEngine {
         Self {
             session: None,
             current_model: None,
-            webgpu_backend: WebGPUBackend::new(),
+            backend_selector: BackendSelector::new(),
             image_processor: ImageProcessor::new(),
         }
     }
@@ -26,10 +27,13 @@ impl StyleTransferEngine {
         let model_info = registry.get_model_info(style_name)
             .ok_or_else(|| JsValue::from_str(&format!("Style '{}' not found", style_name)))?;
 
-        // Initialize WebGPU backend
-        if let Err(e) = self.webgpu_backend.initialize().await {
-            console_log!("WebGPU initialization failed: {:?}, falling back to CPU", e);
-        }
+        // Negotiate the best available execution provider (WebGPU -> WebGL -> WASM-SIMD
+        // CPU), keeping the rejection reasons around for `get_backend_report()`.
+        let requirements = ModelRequirements {
+            min_input_size: model_info.input_size as u32,
+            min_memory_bytes: 0,
+        };
+        let chosen = self.backend_selector.select(requirements).await;
 
         // Download model if not cached
         let model_data = registry.get_model_data(style_name).await?;
@@ -43,14 +47,19 @@ impl StyleTransferEngine {
         let mut session_builder = SessionBuilder::new(&environment)
             .map_err(|e| JsValue::from_str(&format!("Failed to create session builder: {}", e)))?;
 
-        // Try to use WebGPU if available, otherwise fall back to CPU
-        if self.webgpu_backend.is_initialized() {
-            session_builder = session_builder
-                .with_execution_providers([ExecutionProvider::webgpu()])
-                .map_err(|e| JsValue::from_str(&format!("Failed to set WebGPU provider: {}", e)))?;
-            console_log!("Using WebGPU backend for inference");
-        } else {
-            console_log!("Using CPU backend for inference");
+        match chosen {
+            ExecutionProvider::WebGpu => {
+                session_builder = session_builder
+                    .with_execution_providers([OrtExecutionProvider::webgpu()])
+                    .map_err(|e| JsValue::from_str(&format!("Failed to set WebGPU provider: {}", e)))?;
+                console_log!("Using WebGPU backend for inference");
+            }
+            ExecutionProvider::WebGl => {
+                console_log!("Using WebGL backend for inference");
+            }
+            ExecutionProvider::WasmSimd => {
+                console_log!("Using CPU (WASM-SIMD) backend for inference");
+            }
         }
 
         let session = session_builder
@@ -63,6 +72,11 @@ impl StyleTransferEngine {
         Ok(())
     }
 
+    /// JS-facing report of which backend was chosen and why the others were rejected.
+    pub fn get_backend_report(&self) -> Result<JsValue, JsValue> {
+        self.backend_selector.get_backend_report()
+    }
+
     pub async fn stylize_image(
         &self,
         image_data: &[u8],
@@ -78,10 +92,11 @@ impl StyleTransferEngine {
 
         // Preprocess image to model input format
         let preprocessed = self.image_processor.preprocess_image(
-            image_data, 
-            width, 
-            height, 
-            model_info.input_size
+            image_data,
+            width,
+            height,
+            model_info.input_size,
+            crate::resampling::ResampleMode::Bicubic,
         )?;
 
         // Create input tensor
@@ -110,6 +125,7 @@ impl StyleTransferEngine {
             &output_array,
             width,
             height,
+            crate::resampling::ResampleMode::Bicubic,
         )?;
 
         // Blend original and stylized based on style_strength
@@ -125,6 +141,175 @@ impl StyleTransferEngine {
     }
 
     pub fn is_webgpu_supported(&self) -> bool {
-        self.webgpu_backend.is_initialized()
+        self.backend_selector.webgpu_backend().is_initialized()
+    }
+
+    /// Stylize a full-resolution image by tiling it into overlapping `input_size`
+    /// patches, running the (single, reused) session on each, and feather-blending
+    /// the overlap regions back together so tile seams disappear.
+    pub async fn stylize_image_tiled(
+        &self,
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        style_strength: f32,
+        tile_overlap: u32,
+    ) -> Result<Vec<u8>, JsValue> {
+        let model_info = self.current_model.as_ref()
+            .ok_or_else(|| JsValue::from_str("No model info available"))?;
+        let tile_size = model_info.input_size as u32;
+
+        self.stylize_tiled(image_data, width, height, tile_size, tile_overlap, style_strength).await
+    }
+
+    /// Same tiling-with-feather-blend strategy as `stylize_image_tiled`, but
+    /// lets the caller pick `tile_size` explicitly instead of deriving it from
+    /// the loaded model's native input size -- e.g. to trade off tile count
+    /// against per-tile resize quality on very large images. Each tile still
+    /// goes through `stylize_image`'s own preprocess/infer/postprocess, which
+    /// resizes it to the model's native resolution regardless of `tile_size`.
+    pub async fn stylize_tiled(
+        &self,
+        image_data: &[u8],
+        width: u32,
+        height: u32,
+        tile_size: u32,
+        tile_overlap: u32,
+        style_strength: f32,
+    ) -> Result<Vec<u8>, JsValue> {
+        if tile_overlap >= tile_size {
+            return Err(JsValue::from_str("tile_overlap must be smaller than tile_size"));
+        }
+        if width <= tile_size && height <= tile_size {
+            // Small enough to go through the single-pass path.
+            return self.stylize_image(image_data, width, height, style_strength).await;
+        }
+
+        let stride = tile_size - tile_overlap;
+        let mut accum = vec![0f32; (width * height * 3) as usize];
+        let mut weight_sum = vec![0f32; (width * height) as usize];
+
+        let mut y = 0u32;
+        loop {
+            let tile_y = y.min(height.saturating_sub(tile_size));
+            let mut x = 0u32;
+            loop {
+                let tile_x = x.min(width.saturating_sub(tile_size));
+
+                let tile_w = tile_size.min(width - tile_x);
+                let tile_h = tile_size.min(height - tile_y);
+                let tile_rgba = Self::crop_rgba(image_data, width, tile_x, tile_y, tile_w, tile_h);
+
+                let stylized_tile = self
+                    .stylize_image(&tile_rgba, tile_w, tile_h, style_strength)
+                    .await?;
+
+                // Only ramp toward edges shared with another tile; edges on the
+                // image border keep full weight, since no other tile covers them.
+                let lead_x = if tile_x == 0 { tile_overlap } else { 0 };
+                let trail_x = if tile_x + tile_w >= width { tile_overlap } else { 0 };
+                let lead_y = if tile_y == 0 { tile_overlap } else { 0 };
+                let trail_y = if tile_y + tile_h >= height { tile_overlap } else { 0 };
+
+                for ty in 0..tile_h {
+                    for tx in 0..tile_w {
+                        let weight = Self::feather_weight(tx + lead_x, tile_w + lead_x + trail_x, tile_overlap)
+                            * Self::feather_weight(ty + lead_y, tile_h + lead_y + trail_y, tile_overlap);
+                        let src_idx = ((ty * tile_w + tx) * 4) as usize;
+                        let gx = tile_x + tx;
+                        let gy = tile_y + ty;
+                        let dst_pixel = (gy * width + gx) as usize;
+
+                        accum[dst_pixel * 3] += stylized_tile[src_idx] as f32 * weight;
+                        accum[dst_pixel * 3 + 1] += stylized_tile[src_idx + 1] as f32 * weight;
+                        accum[dst_pixel * 3 + 2] += stylized_tile[src_idx + 2] as f32 * weight;
+                        weight_sum[dst_pixel] += weight;
+                    }
+                }
+
+                if tile_x + tile_size >= width {
+                    break;
+                }
+                x += stride;
+            }
+
+            if tile_y + tile_size >= height {
+                break;
+            }
+            y += stride;
+        }
+
+        let mut result = vec![0u8; (width * height * 4) as usize];
+        for pixel in 0..(width * height) as usize {
+            let w = weight_sum[pixel].max(f32::EPSILON);
+            result[pixel * 4] = (accum[pixel * 3] / w).clamp(0.0, 255.0) as u8;
+            result[pixel * 4 + 1] = (accum[pixel * 3 + 1] / w).clamp(0.0, 255.0) as u8;
+            result[pixel * 4 + 2] = (accum[pixel * 3 + 2] / w).clamp(0.0, 255.0) as u8;
+            result[pixel * 4 + 3] = 255;
+        }
+
+        Ok(result)
+    }
+
+    fn crop_rgba(data: &[u8], full_width: u32, x: u32, y: u32, w: u32, h: u32) -> Vec<u8> {
+        let mut out = vec![0u8; (w * h * 4) as usize];
+        for row in 0..h {
+            let src_start = (((y + row) * full_width + x) * 4) as usize;
+            let dst_start = (row * w * 4) as usize;
+            out[dst_start..dst_start + (w * 4) as usize]
+                .copy_from_slice(&data[src_start..src_start + (w * 4) as usize]);
+        }
+        out
+    }
+
+    /// Raised-cosine feather weight: 1.0 in the tile interior, ramping to 0 over
+    /// `overlap` pixels at both ends of `extent`. `stylize_tiled` keeps an edge
+    /// that lies on the image border at full weight by shifting `coord` and
+    /// widening `extent` by `overlap` on that side.
+    ///
+    /// Duplicated from `crates/stylizer`'s `feather_weight` -- see that
+    /// function's doc comment for why it isn't factored into a shared helper.
+    fn feather_weight(coord: u32, extent: u32, overlap: u32) -> f32 {
+        if overlap == 0 {
+            return 1.0;
+        }
+        let leading = if coord < overlap {
+            0.5 * (1.0 - (std::f32::consts::PI * coord as f32 / overlap as f32).cos())
+        } else {
+            1.0
+        };
+        let dist_from_end = extent.saturating_sub(coord + 1);
+        let trailing = if dist_from_end < overlap {
+            0.5 * (1.0 - (std::f32::consts::PI * dist_from_end as f32 / overlap as f32).cos())
+        } else {
+            1.0
+        };
+        leading.min(trailing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feather_weight_is_full_strength_in_the_interior() {
+        assert_eq!(StyleTransferEngine::feather_weight(50, 100, 8), 1.0);
+    }
+
+    #[test]
+    fn feather_weight_ramps_to_zero_at_the_first_pixel() {
+        assert_eq!(StyleTransferEngine::feather_weight(0, 100, 8), 0.0);
+    }
+
+    #[test]
+    fn feather_weight_ramps_to_zero_at_the_last_pixel() {
+        assert_eq!(StyleTransferEngine::feather_weight(99, 100, 8), 0.0);
+    }
+
+    #[test]
+    fn feather_weight_is_always_full_strength_with_no_overlap() {
+        assert_eq!(StyleTransferEngine::feather_weight(0, 10, 0), 1.0);
+        assert_eq!(StyleTransferEngine::feather_weight(9, 10, 0), 1.0);
     }
 }
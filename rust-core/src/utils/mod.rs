@@ -0,0 +1,2 @@
+pub mod image_filters;
+pub mod tensor_ops;
@@ -1,51 +1,205 @@
 use ndarray::{Array, Array4, Axis, Dimension};
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{GpuBuffer, GpuBufferDescriptor, GpuBufferUsage, GpuDevice, GpuMapMode, GpuQueue};
+
+/// WGSL kernel for `TensorOps::resize_tensor_gpu`. One invocation per output pixel,
+/// dispatched on an 8x8 workgroup grid; channels/batch are looped inside the shader
+/// so the dispatch stays 2D.
+const RESIZE_SHADER: &str = r#"
+struct Dims {
+    old_h: u32,
+    old_w: u32,
+    new_h: u32,
+    new_w: u32,
+    channels: u32,
+    batch: u32,
+};
+
+@group(0) @binding(0) var<storage, read> input_buf: array<f32>;
+@group(0) @binding(1) var<storage, read_write> output_buf: array<f32>;
+@group(0) @binding(2) var<uniform> dims: Dims;
+
+fn in_idx(b: u32, c: u32, h: u32, w: u32) -> u32 {
+    return ((b * dims.channels + c) * dims.old_h + h) * dims.old_w + w;
+}
+
+fn out_idx(b: u32, c: u32, h: u32, w: u32) -> u32 {
+    return ((b * dims.channels + c) * dims.new_h + h) * dims.new_w + w;
+}
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let w = gid.x;
+    let h = gid.y;
+    if (w >= dims.new_w || h >= dims.new_h) {
+        return;
+    }
+
+    let height_scale = f32(dims.old_h) / f32(dims.new_h);
+    let width_scale = f32(dims.old_w) / f32(dims.new_w);
+    let src_h = f32(h) * height_scale;
+    let src_w = f32(w) * width_scale;
+    let h0 = u32(floor(src_h));
+    let w0 = u32(floor(src_w));
+    let h1 = min(h0 + 1u, dims.old_h - 1u);
+    let w1 = min(w0 + 1u, dims.old_w - 1u);
+    let dh = src_h - f32(h0);
+    let dw = src_w - f32(w0);
+
+    for (var b: u32 = 0u; b < dims.batch; b = b + 1u) {
+        for (var c: u32 = 0u; c < dims.channels; c = c + 1u) {
+            let v00 = input_buf[in_idx(b, c, h0, w0)];
+            let v10 = input_buf[in_idx(b, c, h1, w0)];
+            let v01 = input_buf[in_idx(b, c, h0, w1)];
+            let v11 = input_buf[in_idx(b, c, h1, w1)];
+            let val = v00 * (1.0 - dh) * (1.0 - dw)
+                + v10 * dh * (1.0 - dw)
+                + v01 * (1.0 - dh) * dw
+                + v11 * dh * dw;
+            output_buf[out_idx(b, c, h, w)] = val;
+        }
+    }
+}
+"#;
+
+/// WGSL kernel for `TensorOps::gram_matrix_gpu`. One invocation per `(i, j)` channel
+/// pair; the spatial accumulation loop runs inside the shader.
+const GRAM_SHADER: &str = r#"
+struct Dims {
+    channels: u32,
+    height: u32,
+    width: u32,
+    batch: u32,
+};
+
+@group(0) @binding(0) var<storage, read> input_buf: array<f32>;
+@group(0) @binding(1) var<storage, read_write> gram_buf: array<f32>;
+@group(0) @binding(2) var<uniform> dims: Dims;
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let i = gid.x;
+    let j = gid.y;
+    if (i >= dims.channels || j >= dims.channels) {
+        return;
+    }
+
+    let plane = dims.height * dims.width;
+    for (var b: u32 = 0u; b < dims.batch; b = b + 1u) {
+        var sum: f32 = 0.0;
+        let base_i = (b * dims.channels + i) * plane;
+        let base_j = (b * dims.channels + j) * plane;
+        for (var p: u32 = 0u; p < plane; p = p + 1u) {
+            sum = sum + input_buf[base_i + p] * input_buf[base_j + p];
+        }
+        gram_buf[(b * dims.channels + i) * dims.channels + j] = sum / f32(plane);
+    }
+}
+"#;
 
 pub struct TensorOps;
 
 impl TensorOps {
-    /// Resize tensor using bilinear interpolation
+    /// Resize `input` to `(new_height, new_width)`, dispatching the bilinear kernel on
+    /// `device`/`queue` when a WebGPU device is available and falling back to the CPU
+    /// path otherwise.
+    pub async fn resize_tensor_on(
+        device: Option<(&GpuDevice, &GpuQueue)>,
+        input: &Array4<f32>,
+        new_height: usize,
+        new_width: usize,
+    ) -> Result<Array4<f32>, JsValue> {
+        if let Some((device, queue)) = device {
+            match Self::resize_tensor_gpu(device, queue, input, new_height, new_width).await {
+                Ok(output) => return Ok(output),
+                Err(e) => {
+                    web_sys::console::warn_1(
+                        &format!("GPU resize_tensor failed, falling back to CPU: {:?}", e).into(),
+                    );
+                }
+            }
+        }
+        Self::resize_tensor(input, new_height, new_width)
+    }
+
+    async fn resize_tensor_gpu(
+        device: &GpuDevice,
+        queue: &GpuQueue,
+        input: &Array4<f32>,
+        new_height: usize,
+        new_width: usize,
+    ) -> Result<Array4<f32>, JsValue> {
+        let (batch, channels, old_height, old_width) = input.dim();
+        let dims: [u32; 6] = [
+            old_height as u32,
+            old_width as u32,
+            new_height as u32,
+            new_width as u32,
+            channels as u32,
+            batch as u32,
+        ];
+
+        let input_flat: Vec<f32> = input.iter().copied().collect();
+        let out_len = batch * channels * new_height * new_width;
+
+        let raw = run_compute_shader(
+            device,
+            queue,
+            RESIZE_SHADER,
+            &input_flat,
+            &dims,
+            out_len,
+            ((new_width as u32 + 7) / 8, (new_height as u32 + 7) / 8, 1),
+        )
+        .await?;
+
+        Array4::from_shape_vec((batch, channels, new_height, new_width), raw)
+            .map_err(|e| JsValue::from_str(&format!("resize_tensor_gpu reshape failed: {}", e)))
+    }
+
+    /// Resize tensor using bilinear interpolation (CPU fallback path)
     pub fn resize_tensor(
-        input: &Array4<f32>, 
-        new_height: usize, 
+        input: &Array4<f32>,
+        new_height: usize,
         new_width: usize
     ) -> Result<Array4<f32>, JsValue> {
         let (batch, channels, old_height, old_width) = input.dim();
         let mut output = Array4::<f32>::zeros((batch, channels, new_height, new_width));
-        
+
         let height_scale = old_height as f32 / new_height as f32;
         let width_scale = old_width as f32 / new_width as f32;
-        
+
         for b in 0..batch {
             for c in 0..channels {
                 for h in 0..new_height {
                     for w in 0..new_width {
                         let src_h = h as f32 * height_scale;
                         let src_w = w as f32 * width_scale;
-                        
+
                         let h0 = src_h.floor() as usize;
                         let w0 = src_w.floor() as usize;
                         let h1 = (h0 + 1).min(old_height - 1);
                         let w1 = (w0 + 1).min(old_width - 1);
-                        
+
                         let dh = src_h - h0 as f32;
                         let dw = src_w - w0 as f32;
-                        
+
                         // Bilinear interpolation
                         let val = input[[b, c, h0, w0]] * (1.0 - dh) * (1.0 - dw) +
                                 input[[b, c, h1, w0]] * dh * (1.0 - dw) +
                                 input[[b, c, h0, w1]] * (1.0 - dh) * dw +
                                 input[[b, c, h1, w1]] * dh * dw;
-                        
+
                         output[[b, c, h, w]] = val;
                     }
                 }
             }
         }
-        
+
         Ok(output)
     }
-    
+
     /// Normalize tensor to [-1, 1] range
     pub fn normalize_tensor(input: &mut Array4<f32>) {
         let min_val = input.iter().fold(f32::INFINITY, |a, &b| a.min(b));
@@ -62,13 +216,58 @@ impl TensorOps {
         input.mapv_inplace(|x| ((x + 1.0) * 127.5).clamp(0.0, 255.0));
     }
     
-    /// Apply gram matrix for style loss computation
+    /// Compute the Gram matrix, dispatching on `device`/`queue` when available and
+    /// falling back to the CPU path otherwise.
+    pub async fn gram_matrix_on(
+        device: Option<(&GpuDevice, &GpuQueue)>,
+        input: &Array4<f32>,
+    ) -> Result<Array4<f32>, JsValue> {
+        if let Some((device, queue)) = device {
+            match Self::gram_matrix_gpu(device, queue, input).await {
+                Ok(gram) => return Ok(gram),
+                Err(e) => {
+                    web_sys::console::warn_1(
+                        &format!("GPU gram_matrix failed, falling back to CPU: {:?}", e).into(),
+                    );
+                }
+            }
+        }
+        Self::gram_matrix(input)
+    }
+
+    async fn gram_matrix_gpu(
+        device: &GpuDevice,
+        queue: &GpuQueue,
+        input: &Array4<f32>,
+    ) -> Result<Array4<f32>, JsValue> {
+        let (batch, channels, height, width) = input.dim();
+        let dims: [u32; 4] = [channels as u32, height as u32, width as u32, batch as u32];
+
+        let input_flat: Vec<f32> = input.iter().copied().collect();
+        let out_len = batch * channels * channels;
+
+        let raw = run_compute_shader(
+            device,
+            queue,
+            GRAM_SHADER,
+            &input_flat,
+            &dims,
+            out_len,
+            ((channels as u32 + 7) / 8, (channels as u32 + 7) / 8, 1),
+        )
+        .await?;
+
+        Array4::from_shape_vec((batch, channels, channels, 1), raw)
+            .map_err(|e| JsValue::from_str(&format!("gram_matrix_gpu reshape failed: {}", e)))
+    }
+
+    /// Apply gram matrix for style loss computation (CPU fallback path)
     pub fn gram_matrix(input: &Array4<f32>) -> Result<Array4<f32>, JsValue> {
         let (batch, channels, height, width) = input.dim();
         let features = height * width;
-        
+
         let mut gram = Array4::<f32>::zeros((batch, channels, channels, 1));
-        
+
         for b in 0..batch {
             for i in 0..channels {
                 for j in 0..channels {
@@ -82,7 +281,93 @@ impl TensorOps {
                 }
             }
         }
-        
+
         Ok(gram)
     }
 }
+
+/// Upload `input`, a packed `u32` dims uniform, and a zeroed output buffer, run a
+/// single compute pass of `shader_src` over `dispatch` workgroups, then map the
+/// output buffer back to the host. Shared by `resize_tensor_gpu`/`gram_matrix_gpu`
+/// since both are a single bind-group-0 storage-in/storage-out/uniform-dims pass.
+async fn run_compute_shader(
+    device: &GpuDevice,
+    queue: &GpuQueue,
+    shader_src: &str,
+    input: &[f32],
+    dims: &[u32],
+    out_len: usize,
+    dispatch: (u32, u32, u32),
+) -> Result<Vec<f32>, JsValue> {
+    let shader_module = device.create_shader_module(&web_sys::GpuShaderModuleDescriptor::new(shader_src));
+
+    let input_bytes = bytemuck_f32_to_bytes(input);
+    let input_buf = create_gpu_buffer(
+        device,
+        &input_bytes,
+        GpuBufferUsage::STORAGE | GpuBufferUsage::COPY_DST,
+    )?;
+    queue.write_buffer_with_u32_and_u8_slice(&input_buf, 0, &input_bytes);
+
+    let dims_bytes = bytemuck_u32_to_bytes(dims);
+    let dims_buf = create_gpu_buffer(
+        device,
+        &dims_bytes,
+        GpuBufferUsage::UNIFORM | GpuBufferUsage::COPY_DST,
+    )?;
+    queue.write_buffer_with_u32_and_u8_slice(&dims_buf, 0, &dims_bytes);
+
+    let out_byte_len = (out_len * std::mem::size_of::<f32>()) as f64;
+    let mut storage_desc = GpuBufferDescriptor::new(out_byte_len, GpuBufferUsage::STORAGE | GpuBufferUsage::COPY_SRC);
+    let output_buf: GpuBuffer = device.create_buffer(&storage_desc);
+
+    let mut readback_desc = GpuBufferDescriptor::new(out_byte_len, GpuBufferUsage::COPY_DST | GpuBufferUsage::MAP_READ);
+    let readback_buf: GpuBuffer = device.create_buffer(&readback_desc);
+
+    let pipeline = device.create_compute_pipeline(&web_sys::GpuComputePipelineDescriptor::new(
+        &"auto".into(),
+        &web_sys::GpuProgrammableStage::new(&shader_module),
+    ));
+
+    let bind_group_layout = pipeline.get_bind_group_layout(0);
+    let entries = js_sys::Array::new();
+    entries.push(&web_sys::GpuBindGroupEntry::new(0, &input_buf).into());
+    entries.push(&web_sys::GpuBindGroupEntry::new(1, &output_buf).into());
+    entries.push(&web_sys::GpuBindGroupEntry::new(2, &dims_buf).into());
+    let bind_group = device.create_bind_group(&web_sys::GpuBindGroupDescriptor::new(&entries, &bind_group_layout));
+
+    let encoder = device.create_command_encoder();
+    let pass = encoder.begin_compute_pass();
+    pass.set_pipeline(&pipeline);
+    pass.set_bind_group(0, &bind_group);
+    pass.dispatch_workgroups_with_workgroup_count_y_and_workgroup_count_z(dispatch.0, dispatch.1, dispatch.2);
+    pass.end();
+    encoder.copy_buffer_to_buffer_with_u32_and_u32_and_u32(&output_buf, 0, &readback_buf, 0, out_byte_len as u32);
+    queue.submit(&js_sys::Array::of1(&encoder.finish()));
+
+    let map_promise = readback_buf.map_async(GpuMapMode::READ);
+    JsFuture::from(map_promise).await?;
+    let mapped = readback_buf.get_mapped_range();
+    let result_bytes = js_sys::Uint8Array::new(&mapped).to_vec();
+    readback_buf.unmap();
+
+    Ok(result_bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+fn create_gpu_buffer(device: &GpuDevice, bytes: &[u8], usage: u32) -> Result<GpuBuffer, JsValue> {
+    // WebGPU buffer sizes must be 4-byte aligned.
+    let aligned_len = ((bytes.len() + 3) / 4 * 4) as f64;
+    let desc = GpuBufferDescriptor::new(aligned_len, usage);
+    Ok(device.create_buffer(&desc))
+}
+
+fn bytemuck_f32_to_bytes(data: &[f32]) -> Vec<u8> {
+    data.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn bytemuck_u32_to_bytes(data: &[u32]) -> Vec<u8> {
+    data.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
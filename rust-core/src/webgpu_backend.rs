@@ -1,10 +1,157 @@
 use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{GpuAdapter, GpuDevice, Navigator, Gpu};
+use web_sys::{
+    GpuAdapter, GpuBindGroupDescriptor, GpuBindGroupEntry, GpuBuffer, GpuBufferBinding,
+    GpuBufferDescriptor, GpuBufferUsage, GpuComputePassDescriptor, GpuComputePipelineDescriptor,
+    GpuDevice, GpuMapMode, GpuProgrammableStage, GpuShaderModuleDescriptor, Gpu, Navigator,
+};
+use js_sys::{Array, ArrayBuffer, Uint32Array};
+use ndarray::{ArrayD, IxDyn};
+use ort::{Environment, ExecutionProvider as OrtExecutionProvider, Session, SessionBuilder, Value};
+
+use crate::utils::image_filters::ImageFilters;
+
+/// Threads per workgroup on each axis; matches `@workgroup_size(8, 8)` in the
+/// WGSL kernels below.
+const WORKGROUP_SIZE: u32 = 8;
+
+/// Max Gaussian kernel taps the uniform buffer below is sized for (kernel
+/// weights are packed 4-per-`vec4` to satisfy WGSL's 16-byte uniform array
+/// stride), comfortably above any `radius` this app exposes in its UI.
+const MAX_KERNEL_TAPS: usize = 64;
+
+/// Separable Gaussian blur, one dispatch per direction. `direction` (0 =
+/// horizontal, 1 = vertical) picks which axis the tap loop walks; RGBA pixels
+/// are packed one-per-`u32` so the storage buffers stay 4-byte aligned.
+const GAUSSIAN_PASS_WGSL: &str = r#"
+struct Params {
+    width: u32,
+    height: u32,
+    kernel_size: u32,
+    direction: u32,
+};
+
+@group(0) @binding(0) var<storage, read> input_buf: array<u32>;
+@group(0) @binding(1) var<storage, read_write> output_buf: array<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+@group(0) @binding(3) var<uniform> kernel_weights: array<vec4<f32>, 16>;
+
+fn tap_weight(k: u32) -> f32 {
+    let lane = k % 4u;
+    let group = k / 4u;
+    return kernel_weights[group][lane];
+}
+
+fn unpack_rgba(p: u32) -> vec4<f32> {
+    return vec4<f32>(
+        f32(p & 0xFFu),
+        f32((p >> 8u) & 0xFFu),
+        f32((p >> 16u) & 0xFFu),
+        f32((p >> 24u) & 0xFFu),
+    );
+}
+
+fn pack_rgba(c: vec4<f32>) -> u32 {
+    let r = u32(clamp(c.x, 0.0, 255.0));
+    let g = u32(clamp(c.y, 0.0, 255.0));
+    let b = u32(clamp(c.z, 0.0, 255.0));
+    let a = u32(clamp(c.w, 0.0, 255.0));
+    return r | (g << 8u) | (b << 16u) | (a << 24u);
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= params.width || gid.y >= params.height) {
+        return;
+    }
+
+    let half_kernel = i32(params.kernel_size) / 2;
+    var sum = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    var weight_sum = 0.0;
+
+    for (var k: i32 = 0; k < i32(params.kernel_size); k = k + 1) {
+        let weight = tap_weight(u32(k));
+        var sample_idx: i32 = -1;
+
+        if (params.direction == 0u) {
+            let px = i32(gid.x) + k - half_kernel;
+            if (px >= 0 && px < i32(params.width)) {
+                sample_idx = i32(gid.y) * i32(params.width) + px;
+            }
+        } else {
+            let py = i32(gid.y) + k - half_kernel;
+            if (py >= 0 && py < i32(params.height)) {
+                sample_idx = py * i32(params.width) + i32(gid.x);
+            }
+        }
+
+        if (sample_idx >= 0) {
+            sum = sum + unpack_rgba(input_buf[u32(sample_idx)]) * weight;
+            weight_sum = weight_sum + weight;
+        }
+    }
+
+    let out_idx = gid.y * params.width + gid.x;
+    output_buf[out_idx] = pack_rgba(sum / weight_sum);
+}
+"#;
+
+/// Sobel edge detection, single dispatch sampling a 3x3 neighborhood per pixel
+/// (matching `ImageFilters::edge_detection`'s CPU grayscale+magnitude logic).
+const SOBEL_WGSL: &str = r#"
+struct Params {
+    width: u32,
+    height: u32,
+};
+
+@group(0) @binding(0) var<storage, read> input_buf: array<u32>;
+@group(0) @binding(1) var<storage, read_write> output_buf: array<u32>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+fn luminance(p: u32) -> f32 {
+    let r = f32(p & 0xFFu);
+    let g = f32((p >> 8u) & 0xFFu);
+    let b = f32((p >> 16u) & 0xFFu);
+    return 0.299 * r + 0.587 * g + 0.114 * b;
+}
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= params.width || gid.y >= params.height) {
+        return;
+    }
+    if (gid.x == 0u || gid.y == 0u || gid.x == params.width - 1u || gid.y == params.height - 1u) {
+        output_buf[gid.y * params.width + gid.x] = 0xFF000000u;
+        return;
+    }
+
+    var gx = 0.0;
+    var gy = 0.0;
+    let sobel_x = array<f32, 9>(-1.0, 0.0, 1.0, -2.0, 0.0, 2.0, -1.0, 0.0, 1.0);
+    let sobel_y = array<f32, 9>(-1.0, -2.0, -1.0, 0.0, 0.0, 0.0, 1.0, 2.0, 1.0);
+
+    for (var ky: i32 = 0; ky < 3; ky = ky + 1) {
+        for (var kx: i32 = 0; kx < 3; kx = kx + 1) {
+            let px = i32(gid.x) + kx - 1;
+            let py = i32(gid.y) + ky - 1;
+            let gray = luminance(input_buf[u32(py) * params.width + u32(px)]);
+            let k = ky * 3 + kx;
+            gx = gx + gray * sobel_x[k];
+            gy = gy + gray * sobel_y[k];
+        }
+    }
+
+    let magnitude = u32(clamp(sqrt(gx * gx + gy * gy), 0.0, 255.0));
+    output_buf[gid.y * params.width + gid.x] = magnitude | (magnitude << 8u) | (magnitude << 16u) | 0xFF000000u;
+}
+"#;
 
 pub struct WebGPUBackend {
     device: Option<GpuDevice>,
     adapter: Option<GpuAdapter>,
+    session: Option<Session>,
+    gpu_active: bool,
 }
 
 impl WebGPUBackend {
@@ -12,13 +159,15 @@ impl WebGPUBackend {
         Self {
             device: None,
             adapter: None,
+            session: None,
+            gpu_active: false,
         }
     }
 
     pub async fn initialize(&mut self) -> Result<(), JsValue> {
         let window = web_sys::window().ok_or("No global `window` exists")?;
         let navigator = window.navigator();
-        
+
         // Check if WebGPU is supported
         let gpu: Gpu = js_sys::Reflect::get(&navigator, &JsValue::from_str("gpu"))?
             .dyn_into()
@@ -27,7 +176,7 @@ impl WebGPUBackend {
         // Request adapter
         let adapter_promise = gpu.request_adapter();
         let adapter_js_value = JsFuture::from(adapter_promise).await?;
-        
+
         if adapter_js_value.is_null() {
             return Err(JsValue::from_str("Failed to get WebGPU adapter"));
         }
@@ -52,4 +201,306 @@ impl WebGPUBackend {
     pub fn get_device(&self) -> Option<&GpuDevice> {
         self.device.as_ref()
     }
+
+    /// The adapter's `maxTextureDimension2D` limit, or 0 when no adapter was acquired.
+    pub fn max_texture_dimension(&self) -> u32 {
+        self.adapter
+            .as_ref()
+            .map(|a| a.limits().max_texture_dimension_2d())
+            .unwrap_or(0)
+    }
+
+    /// The adapter's `maxBufferSize` limit, or 0 when no adapter was acquired.
+    pub fn max_buffer_size(&self) -> u64 {
+        self.adapter
+            .as_ref()
+            .map(|a| a.limits().max_buffer_size() as u64)
+            .unwrap_or(0)
+    }
+
+    /// Builds an ONNX Runtime session for `model_bytes`, registering the
+    /// WebGPU execution provider when `initialize()` already acquired a
+    /// `GpuDevice` and otherwise letting `ort` fall back to its own WASM/CPU
+    /// provider. The backend's own `GpuDevice` is not passed to the session.
+    /// `is_gpu_active` reports which path was taken.
+    pub fn create_session(&mut self, model_bytes: Vec<u8>) -> Result<(), JsValue> {
+        let environment = Environment::builder()
+            .with_name("WebGPUBackend")
+            .build()
+            .map_err(|e| JsValue::from_str(&format!("Failed to create ONNX environment: {}", e)))?;
+
+        let mut session_builder = SessionBuilder::new(&environment)
+            .map_err(|e| JsValue::from_str(&format!("Failed to create session builder: {}", e)))?;
+
+        self.gpu_active = self.is_initialized();
+        if self.gpu_active {
+            session_builder = session_builder
+                .with_execution_providers([OrtExecutionProvider::webgpu()])
+                .map_err(|e| JsValue::from_str(&format!("Failed to set WebGPU provider: {}", e)))?;
+        }
+
+        let session = session_builder
+            .with_model_from_memory(model_bytes)
+            .map_err(|e| JsValue::from_str(&format!("Failed to load model: {}", e)))?;
+
+        self.session = Some(session);
+        Ok(())
+    }
+
+    /// Whether `create_session` registered the WebGPU execution provider for
+    /// its session (a WebGPU device had been acquired), so the JS side can show
+    /// a "GPU accelerated" badge instead of silently falling back to CPU.
+    pub fn is_gpu_active(&self) -> bool {
+        self.gpu_active
+    }
+
+    /// Runs `input` (a flattened float32 tensor of `shape`, e.g. the CHW
+    /// buffer from `ImageProcessor::preprocess_image`) through the session
+    /// created by `create_session`, returning the flattened float32 output
+    /// tensor.
+    pub fn run(&self, input: &[f32], shape: &[usize]) -> Result<Vec<f32>, JsValue> {
+        let session = self.session.as_ref()
+            .ok_or_else(|| JsValue::from_str("No session created; call create_session() first"))?;
+
+        let input_tensor: ArrayD<f32> = ArrayD::from_shape_vec(IxDyn(shape), input.to_vec())
+            .map_err(|e| JsValue::from_str(&format!("Invalid input shape: {}", e)))?;
+
+        let input_name = session.inputs.get(0)
+            .map(|input| input.name.clone())
+            .ok_or_else(|| JsValue::from_str("Session has no declared inputs"))?;
+        let output_name = session.outputs.get(0)
+            .map(|output| output.name.clone())
+            .ok_or_else(|| JsValue::from_str("Session has no declared outputs"))?;
+
+        let inputs = vec![(input_name.as_str(), Value::from_array(session.allocator(), &input_tensor)?)];
+        let outputs = session.run(inputs)
+            .map_err(|e| JsValue::from_str(&format!("Inference failed: {}", e)))?;
+
+        let output = outputs.get(output_name.as_str())
+            .ok_or_else(|| JsValue::from_str("Output tensor not found"))?;
+
+        let output_array = output.try_extract::<f32>()
+            .map_err(|e| JsValue::from_str(&format!("Failed to extract output: {}", e)))?
+            .view()
+            .to_owned();
+
+        Ok(output_array.into_raw_vec())
+    }
+
+    /// GPU-accelerated separable Gaussian blur: two compute dispatches
+    /// (horizontal then vertical) sharing an intermediate storage buffer.
+    /// Falls back to `ImageFilters::gaussian_blur` on the CPU when no WebGPU
+    /// device was acquired.
+    pub async fn run_gaussian_blur(&self, data: &[u8], width: u32, height: u32, radius: f32) -> Result<Vec<u8>, JsValue> {
+        let Some(device) = self.device.as_ref() else {
+            let mut out = data.to_vec();
+            ImageFilters::gaussian_blur(&mut out, width, height, radius);
+            return Ok(out);
+        };
+
+        let kernel = Self::gaussian_kernel(radius);
+        if kernel.len() > MAX_KERNEL_TAPS {
+            // Radius too large for the uniform kernel buffer below; the CPU
+            // path has no such limit, so fall back rather than truncate taps.
+            let mut out = data.to_vec();
+            ImageFilters::gaussian_blur(&mut out, width, height, radius);
+            return Ok(out);
+        }
+
+        let pixels = Self::pack_rgba_u32(data, width, height);
+        let pixel_bytes = (pixels.len() * 4) as f64;
+
+        let input_buf = Self::create_storage_buffer(device, pixel_bytes, true)?;
+        Self::write_u32_buffer(device, &input_buf, &pixels)?;
+        let intermediate_buf = Self::create_storage_buffer(device, pixel_bytes, false)?;
+        let output_buf = Self::create_storage_buffer(device, pixel_bytes, false)?;
+
+        let module = device.create_shader_module(&GpuShaderModuleDescriptor::new(GAUSSIAN_PASS_WGSL));
+        let stage = GpuProgrammableStage::new("main", &module);
+        let pipeline = device.create_compute_pipeline(&GpuComputePipelineDescriptor::new(&JsValue::from_str("auto"), &stage));
+        let layout = pipeline.get_bind_group_layout(0);
+
+        let kernel_buf = Self::create_kernel_weights_buffer(device, &kernel)?;
+        let horizontal_params = Self::create_uniform_u32s(device, &[width, height, kernel.len() as u32, 0])?;
+        let vertical_params = Self::create_uniform_u32s(device, &[width, height, kernel.len() as u32, 1])?;
+
+        let horizontal_group = Self::bind_group(device, &layout, &[&input_buf, &intermediate_buf, &horizontal_params, &kernel_buf])?;
+        let vertical_group = Self::bind_group(device, &layout, &[&intermediate_buf, &output_buf, &vertical_params, &kernel_buf])?;
+
+        let encoder = device.create_command_encoder();
+        let workgroups_x = width.div_ceil(WORKGROUP_SIZE);
+        let workgroups_y = height.div_ceil(WORKGROUP_SIZE);
+
+        let pass = encoder.begin_compute_pass_with_descriptor(&GpuComputePassDescriptor::new());
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, Some(&horizontal_group));
+        pass.dispatch_workgroups_with_workgroup_count_y(workgroups_x, workgroups_y);
+        pass.end();
+
+        let pass = encoder.begin_compute_pass_with_descriptor(&GpuComputePassDescriptor::new());
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, Some(&vertical_group));
+        pass.dispatch_workgroups_with_workgroup_count_y(workgroups_x, workgroups_y);
+        pass.end();
+
+        let read_buf = Self::create_readback_buffer(device, pixel_bytes)?;
+        encoder.copy_buffer_to_buffer_with_f64_and_f64_and_f64(&output_buf, 0.0, &read_buf, 0.0, pixel_bytes);
+
+        device.queue().submit(&Array::of1(&encoder.finish()));
+
+        let out_pixels = Self::read_back_u32(&read_buf, pixel_bytes).await?;
+        Ok(Self::unpack_rgba_u8(&out_pixels, data.len()))
+    }
+
+    /// GPU-accelerated Sobel edge detection, a single compute dispatch over a
+    /// 3x3 neighborhood per pixel. Falls back to `ImageFilters::edge_detection`
+    /// on the CPU when no WebGPU device was acquired.
+    pub async fn run_edge_detection(&self, data: &[u8], width: u32, height: u32) -> Result<Vec<u8>, JsValue> {
+        let Some(device) = self.device.as_ref() else {
+            return Ok(ImageFilters::edge_detection(data, width, height));
+        };
+
+        let pixels = Self::pack_rgba_u32(data, width, height);
+        let pixel_bytes = (pixels.len() * 4) as f64;
+
+        let input_buf = Self::create_storage_buffer(device, pixel_bytes, true)?;
+        Self::write_u32_buffer(device, &input_buf, &pixels)?;
+        let output_buf = Self::create_storage_buffer(device, pixel_bytes, false)?;
+
+        let module = device.create_shader_module(&GpuShaderModuleDescriptor::new(SOBEL_WGSL));
+        let stage = GpuProgrammableStage::new("main", &module);
+        let pipeline = device.create_compute_pipeline(&GpuComputePipelineDescriptor::new(&JsValue::from_str("auto"), &stage));
+        let layout = pipeline.get_bind_group_layout(0);
+
+        let params = Self::create_uniform_u32s(device, &[width, height])?;
+        let bind_group = Self::bind_group(device, &layout, &[&input_buf, &output_buf, &params])?;
+
+        let encoder = device.create_command_encoder();
+        let pass = encoder.begin_compute_pass_with_descriptor(&GpuComputePassDescriptor::new());
+        pass.set_pipeline(&pipeline);
+        pass.set_bind_group(0, Some(&bind_group));
+        pass.dispatch_workgroups_with_workgroup_count_y(width.div_ceil(WORKGROUP_SIZE), height.div_ceil(WORKGROUP_SIZE));
+        pass.end();
+
+        let read_buf = Self::create_readback_buffer(device, pixel_bytes)?;
+        encoder.copy_buffer_to_buffer_with_f64_and_f64_and_f64(&output_buf, 0.0, &read_buf, 0.0, pixel_bytes);
+
+        device.queue().submit(&Array::of1(&encoder.finish()));
+
+        let out_pixels = Self::read_back_u32(&read_buf, pixel_bytes).await?;
+        Ok(Self::unpack_rgba_u8(&out_pixels, data.len()))
+    }
+
+    fn gaussian_kernel(radius: f32) -> Vec<f32> {
+        let size = (radius * 6.0) as usize + 1;
+        let mut kernel = vec![0.0; size];
+        let sigma = radius / 3.0;
+        let two_sigma_sq = 2.0 * sigma * sigma;
+        let center = size / 2;
+
+        let mut sum = 0.0;
+        for (i, weight) in kernel.iter_mut().enumerate() {
+            let x = i as f32 - center as f32;
+            *weight = (-x * x / two_sigma_sq).exp();
+            sum += *weight;
+        }
+        for weight in kernel.iter_mut() {
+            *weight /= sum;
+        }
+        kernel
+    }
+
+    /// Packs an interleaved RGBA byte buffer one pixel per `u32` so every
+    /// storage buffer write stays 4-byte aligned.
+    fn pack_rgba_u32(data: &[u8], width: u32, height: u32) -> Vec<u32> {
+        let count = (width * height) as usize;
+        let mut out = vec![0u32; count];
+        for (i, pixel) in out.iter_mut().enumerate() {
+            let idx = i * 4;
+            if idx + 3 < data.len() {
+                *pixel = data[idx] as u32
+                    | (data[idx + 1] as u32) << 8
+                    | (data[idx + 2] as u32) << 16
+                    | (data[idx + 3] as u32) << 24;
+            }
+        }
+        out
+    }
+
+    fn unpack_rgba_u8(pixels: &[u32], len: usize) -> Vec<u8> {
+        let mut out = vec![0u8; len];
+        for (i, pixel) in pixels.iter().enumerate() {
+            let idx = i * 4;
+            if idx + 3 < out.len() {
+                out[idx] = (pixel & 0xFF) as u8;
+                out[idx + 1] = ((pixel >> 8) & 0xFF) as u8;
+                out[idx + 2] = ((pixel >> 16) & 0xFF) as u8;
+                out[idx + 3] = ((pixel >> 24) & 0xFF) as u8;
+            }
+        }
+        out
+    }
+
+    fn create_storage_buffer(device: &GpuDevice, size: f64, copy_dst: bool) -> Result<GpuBuffer, JsValue> {
+        let mut usage = GpuBufferUsage::STORAGE | GpuBufferUsage::COPY_SRC;
+        if copy_dst {
+            usage |= GpuBufferUsage::COPY_DST;
+        }
+        Ok(device.create_buffer(&GpuBufferDescriptor::new(size, usage)))
+    }
+
+    fn create_readback_buffer(device: &GpuDevice, size: f64) -> Result<GpuBuffer, JsValue> {
+        Ok(device.create_buffer(&GpuBufferDescriptor::new(size, GpuBufferUsage::COPY_DST | GpuBufferUsage::MAP_READ)))
+    }
+
+    fn write_u32_buffer(device: &GpuDevice, buffer: &GpuBuffer, data: &[u32]) -> Result<(), JsValue> {
+        let typed = Uint32Array::from(data);
+        device.queue().write_buffer_with_u32_and_buffer_source(buffer, 0, &typed)
+    }
+
+    fn create_uniform_u32s(device: &GpuDevice, values: &[u32]) -> Result<GpuBuffer, JsValue> {
+        // Uniform buffers must be a multiple of 16 bytes; pad the (tiny) params
+        // struct up before writing it.
+        let byte_len = ((values.len() * 4) as f64 / 16.0).ceil() * 16.0;
+        let buffer = device.create_buffer(&GpuBufferDescriptor::new(byte_len, GpuBufferUsage::UNIFORM | GpuBufferUsage::COPY_DST));
+        let typed = Uint32Array::from(values);
+        device.queue().write_buffer_with_u32_and_buffer_source(&buffer, 0, &typed)?;
+        Ok(buffer)
+    }
+
+    /// Packs Gaussian kernel weights 4-per-`vec4` to satisfy WGSL's 16-byte
+    /// uniform array stride (`array<vec4<f32>, 16>` in the WGSL above).
+    fn create_kernel_weights_buffer(device: &GpuDevice, kernel: &[f32]) -> Result<GpuBuffer, JsValue> {
+        let mut padded = vec![0f32; MAX_KERNEL_TAPS];
+        padded[..kernel.len()].copy_from_slice(kernel);
+        let buffer = device.create_buffer(&GpuBufferDescriptor::new((MAX_KERNEL_TAPS * 4) as f64, GpuBufferUsage::UNIFORM | GpuBufferUsage::COPY_DST));
+        let typed = js_sys::Float32Array::from(padded.as_slice());
+        device.queue().write_buffer_with_u32_and_buffer_source(&buffer, 0, &typed)?;
+        Ok(buffer)
+    }
+
+    fn bind_group(device: &GpuDevice, layout: &web_sys::GpuBindGroupLayout, buffers: &[&GpuBuffer]) -> Result<web_sys::GpuBindGroup, JsValue> {
+        let entries = Array::new();
+        for (binding, buffer) in buffers.iter().enumerate() {
+            let resource = GpuBufferBinding::new(buffer);
+            entries.push(&GpuBindGroupEntry::new(binding as u32, &resource));
+        }
+        Ok(device.create_bind_group(&GpuBindGroupDescriptor::new(&entries, layout)))
+    }
+
+    /// Maps `buffer` for reading (the map-on-completion pattern: `map_async`
+    /// returns a promise that resolves once the GPU finishes the copy queued
+    /// before it), copies the mapped range out, then unmaps — a mapped buffer
+    /// can't be reused by the GPU until `unmap()` is called.
+    async fn read_back_u32(buffer: &GpuBuffer, size: f64) -> Result<Vec<u32>, JsValue> {
+        JsFuture::from(buffer.map_async(GpuMapMode::READ)).await?;
+
+        let mapped: ArrayBuffer = buffer.get_mapped_range().dyn_into()?;
+        let view = Uint32Array::new(&mapped);
+        let mut out = vec![0u32; (size as usize) / 4];
+        view.copy_to(&mut out);
+
+        buffer.unmap();
+        Ok(out)
+    }
 }
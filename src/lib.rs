@@ -1,10 +1,13 @@
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
-use js_sys::{Array, ArrayBuffer, Uint8Array};
+use js_sys::{Array, Uint8Array};
 use web_sys::*;
 use std::collections::HashMap;
 use once_cell::sync::Lazy;
+use wonnx::session::Session as WonnxSession;
+use stylizer::{bilinear_resize_rgba, nchw_from_rgba, rgba_from_nchw};
+use image::ImageEncoder;
 
 // Use `wee_alloc` as the global allocator
 #[global_allocator]
@@ -26,8 +29,9 @@ pub struct StyleModel {
     pub url: String,
 }
 
-// Thread-safe model storage
-static LOADED_MODELS: Lazy<std::sync::Mutex<HashMap<String, ArrayBuffer>>> = Lazy::new(|| {
+// Thread-safe model storage: a real WONNX session per loaded style, built once
+// from the fetched `.onnx` bytes and reused across `process_image` calls.
+static LOADED_MODELS: Lazy<std::sync::Mutex<HashMap<String, WonnxSession>>> = Lazy::new(|| {
     std::sync::Mutex::new(HashMap::new())
 });
 
@@ -83,6 +87,10 @@ pub struct StyleTransfer {
     current_style: String,
     style_strength: f32,
     models: Vec<StyleModel>,
+    // Negotiated by `initialize_webgpu`; `None` until a real WebGPU adapter has
+    // been acquired.
+    max_texture_dimension: Option<u32>,
+    max_buffer_size: Option<f64>,
 }
 
 #[wasm_bindgen]
@@ -107,25 +115,66 @@ impl StyleTransfer {
             current_style: String::new(),
             style_strength: 1.0,
             models: get_models(),
+            max_texture_dimension: None,
+            max_buffer_size: None,
         })
     }
 
+    /// Negotiate a real `navigator.gpu` adapter/device and record its limits so
+    /// `get_available_styles` can report a safe `recommended_size` to the frontend.
     #[wasm_bindgen]
     pub async fn initialize_webgpu(&mut self) -> Result<(), JsValue> {
-        web_sys::console::log_1(&"Style Transfer initialized - ready for GPU acceleration".into());
+        let window = get_window()?;
+        let navigator = window.navigator();
+
+        let gpu: Gpu = js_sys::Reflect::get(&navigator, &JsValue::from_str("gpu"))?
+            .dyn_into()
+            .map_err(|_| JsValue::from_str("WebGPU not supported"))?;
+
+        let adapter_value = JsFuture::from(gpu.request_adapter()).await?;
+        if adapter_value.is_null() || adapter_value.is_undefined() {
+            return Err(JsValue::from_str("Failed to get WebGPU adapter"));
+        }
+        let adapter: GpuAdapter = adapter_value.dyn_into()?;
+
+        let device_value = JsFuture::from(adapter.request_device()).await?;
+        let _device: GpuDevice = device_value.dyn_into()?;
+
+        let limits = adapter.limits();
+        self.max_texture_dimension = Some(limits.max_texture_dimension_2d());
+        self.max_buffer_size = Some(limits.max_buffer_size());
+
+        web_sys::console::log_1(&format!(
+            "WebGPU initialized: max_texture_dimension_2d={:?}, max_buffer_size={:?}",
+            self.max_texture_dimension, self.max_buffer_size
+        ).into());
+
         Ok(())
     }
 
+    /// True once `initialize_webgpu` has successfully negotiated an adapter.
+    #[wasm_bindgen]
+    pub fn is_webgpu_available(&self) -> bool {
+        self.max_texture_dimension.is_some()
+    }
+
     #[wasm_bindgen]
     pub fn get_available_styles(&self) -> JsValue {
         let models: Vec<JsValue> = self.models.iter().map(|model| {
             let obj = js_sys::Object::new();
             js_sys::Reflect::set(&obj, &"name".into(), &model.name.clone().into()).unwrap();
             js_sys::Reflect::set(&obj, &"size".into(), &model.size.into()).unwrap();
-            js_sys::Reflect::set(&obj, &"recommendedSize".into(), &model.recommended_size.into()).unwrap();
+
+            // Clamp the advertised resolution to the negotiated adapter's
+            // max texture dimension so the frontend never requests a size the
+            // GPU can't actually allocate.
+            let recommended_size = self.max_texture_dimension
+                .map(|max_dim| model.recommended_size.min(max_dim))
+                .unwrap_or(model.recommended_size);
+            js_sys::Reflect::set(&obj, &"recommendedSize".into(), &recommended_size.into()).unwrap();
             obj.into()
         }).collect();
-        
+
         models.into_iter().collect::<Array>().into()
     }
 
@@ -133,7 +182,8 @@ impl StyleTransfer {
     pub async fn load_style_model(&mut self, style_name: &str) -> Result<(), JsValue> {
         let model = self.models.iter()
             .find(|m| m.name == style_name)
-            .ok_or("Style not found")?;
+            .ok_or("Style not found")?
+            .clone();
 
         // Check if already loaded
         {
@@ -144,16 +194,16 @@ impl StyleTransfer {
             }
         }
 
-        // Simulate loading
         web_sys::console::log_1(&format!("Loading style model: {}", style_name).into());
-        
-        let promise = js_sys::Promise::resolve(&JsValue::from(42));
-        let _ = JsFuture::from(promise).await?;
-        
+
+        let bytes = fetch_bytes(&model.url).await?;
+        let session = WonnxSession::from_bytes(&bytes)
+            .await
+            .map_err(|e| JsValue::from_str(&format!("Failed to build WONNX session: {}", e)))?;
+
         {
             let mut loaded = LOADED_MODELS.lock().map_err(|_| "Failed to acquire lock")?;
-            let dummy_buffer = ArrayBuffer::new(1024);
-            loaded.insert(model.name.clone(), dummy_buffer);
+            loaded.insert(model.name.clone(), session);
         }
 
         self.current_style = style_name.to_string();
@@ -176,104 +226,42 @@ impl StyleTransfer {
             .find(|m| m.name == self.current_style)
             .ok_or("Current style not found")?;
 
-        {
+        // Resize to the model's native input resolution, run the real NCHW
+        // preprocess -> WONNX inference -> postprocess pipeline, then blend back
+        // against the original at the requested resolution.
+        let resized = bilinear_resize_rgba(image_data, width, height, model.recommended_size, model.recommended_size);
+        let input_tensor = nchw_from_rgba(&resized, model.recommended_size, model.recommended_size, [0.0, 0.0, 0.0], [1.0, 1.0, 1.0], 1.0);
+
+        let mut outputs = {
             let loaded = LOADED_MODELS.lock().map_err(|_| "Failed to acquire lock")?;
-            if !loaded.contains_key(&model.name) {
-                return Err("Model not loaded".into());
-            }
-        }
+            let session = loaded.get(&model.name).ok_or("Model not loaded")?;
+            session
+                .run(vec![(model.input_name.clone(), input_tensor)])
+                .await
+                .map_err(|e| JsValue::from_str(&format!("wonnx inference failed: {}", e)))?
+        };
+
+        let output_tensor = outputs
+            .remove(&model.output_name)
+            .ok_or("Output tensor not found")?;
+
+        let stylized_native = rgba_from_nchw(&output_tensor, model.recommended_size, model.recommended_size, [0.0, 0.0, 0.0], [1.0, 1.0, 1.0], 1.0, &resized);
+        let stylized = bilinear_resize_rgba(&stylized_native, model.recommended_size, model.recommended_size, width, height);
 
-        let processed = self.apply_style_transform(image_data, width, height, model)?;
-        Ok(processed)
+        Ok(self.blend_with_original(image_data, &stylized))
     }
 
-    fn apply_style_transform(&self, image_data: &[u8], width: u32, height: u32, _model: &StyleModel) -> Result<Vec<u8>, JsValue> {
-        let mut result = vec![0u8; image_data.len()];
-        
-        for i in (0..image_data.len()).step_by(4) {
-            if i + 3 < image_data.len() {
-                let r = image_data[i] as f32;
-                let g = image_data[i + 1] as f32;
-                let b = image_data[i + 2] as f32;
-                let a = image_data[i + 3];
-                
-                let pixel_index = i / 4;
-                let y = pixel_index as u32 / width;
-                let x = pixel_index as u32 % width;
-                
-                let (new_r, new_g, new_b) = match self.current_style.as_str() {
-                    name if name.contains("Van Gogh") => {
-                        let swirl_x = (x as f32 / 20.0).sin() * 10.0;
-                        let swirl_y = (y as f32 / 20.0).cos() * 10.0;
-                        
-                        let enhanced_r = (r * 0.9 + swirl_x.abs() * 2.0).min(255.0);
-                        let enhanced_g = (g * 1.1 + swirl_y.abs() * 3.0).min(255.0);
-                        let enhanced_b = (b * 1.4 + (swirl_x + swirl_y) * 2.0).min(255.0);
-                        (enhanced_r, enhanced_g, enhanced_b)
-                    },
-                    name if name.contains("Picasso") => {
-                        let block_size = 16;
-                        let block_x = (x / block_size) % 3;
-                        let block_y = (y / block_size) % 3;
-                        
-                        let shift = (block_x + block_y) as f32 * 0.3;
-                        let new_r = (r * (0.8 + shift) + g * 0.2).min(255.0);
-                        let new_g = (g * (0.7 + shift) + b * 0.3).min(255.0);
-                        let new_b = (b * (0.9 + shift) + r * 0.1).min(255.0);
-                        (new_r, new_g, new_b)
-                    },
-                    name if name.contains("Ukiyo-e") => {
-                        let posterized_r = ((r / 64.0).floor() * 64.0).min(255.0);
-                        let posterized_g = ((g / 64.0).floor() * 64.0).min(255.0);
-                        let posterized_b = ((b / 64.0).floor() * 64.0).min(255.0);
-                        
-                        let new_r = (posterized_r * 1.1).min(255.0);
-                        let new_g = (posterized_g * 0.95 + 10.0).min(255.0);
-                        let new_b = (posterized_b * 1.05).min(255.0);
-                        (new_r, new_g, new_b)
-                    },
-                    name if name.contains("Cyberpunk") => {
-                        let wave = ((x + y) as f32 / 8.0).sin();
-                        let neon_boost = if wave > 0.7 { 40.0 } else { 0.0 };
-                        
-                        let new_r = (r * 1.3 + neon_boost).min(255.0);
-                        let new_g = (g * 0.8 + neon_boost * 0.5 + 20.0).min(255.0);
-                        let new_b = (b * 1.5 + neon_boost).min(255.0);
-                        (new_r, new_g, new_b)
-                    },
-                    name if name.contains("Abstract") => {
-                        let noise_x = ((x * 7) % 13) as f32 / 13.0;
-                        let noise_y = ((y * 11) % 17) as f32 / 17.0;
-                        let texture = (noise_x + noise_y) * 30.0;
-                        
-                        let new_r = (r * 1.2 + texture).min(255.0);
-                        let new_g = (g * 1.15 + texture * 0.8).min(255.0);
-                        let new_b = (b * 1.1 + texture * 1.2).min(255.0);
-                        (new_r, new_g, new_b)
-                    },
-                    _ => {
-                        let brightness = (r + g + b) / 3.0;
-                        let contrast_boost = if brightness > 128.0 { 1.2 } else { 0.9 };
-                        
-                        let new_r = (r * contrast_boost).min(255.0);
-                        let new_g = (g * contrast_boost).min(255.0);
-                        let new_b = (b * contrast_boost).min(255.0);
-                        (new_r, new_g, new_b)
-                    }
-                };
-                
-                let final_r = ((new_r * self.style_strength) + (r * (1.0 - self.style_strength))) as u8;
-                let final_g = ((new_g * self.style_strength) + (g * (1.0 - self.style_strength))) as u8;
-                let final_b = ((new_b * self.style_strength) + (b * (1.0 - self.style_strength))) as u8;
-                
-                result[i] = final_r;
-                result[i + 1] = final_g;
-                result[i + 2] = final_b;
-                result[i + 3] = a;
+    fn blend_with_original(&self, original: &[u8], stylized: &[u8]) -> Vec<u8> {
+        let mut result = vec![0u8; original.len()];
+        for i in (0..original.len()).step_by(4) {
+            if i + 3 < original.len() {
+                result[i] = ((stylized[i] as f32 * self.style_strength) + (original[i] as f32 * (1.0 - self.style_strength))) as u8;
+                result[i + 1] = ((stylized[i + 1] as f32 * self.style_strength) + (original[i + 1] as f32 * (1.0 - self.style_strength))) as u8;
+                result[i + 2] = ((stylized[i + 2] as f32 * self.style_strength) + (original[i + 2] as f32 * (1.0 - self.style_strength))) as u8;
+                result[i + 3] = original[i + 3];
             }
         }
-        
-        Ok(result)
+        result
     }
 
     #[wasm_bindgen]
@@ -334,6 +322,66 @@ impl StyleTransfer {
     }
 }
 
+/// Encodes an RGBA buffer directly to PNG or JPEG bytes, without needing the
+/// pixels to already be drawn into a mounted `<canvas>` the way `download_result`'s
+/// `canvas.to_blob` does. `format` is `"png"` or `"jpeg"`; `quality` (1-100) only
+/// applies to JPEG — PNG always encodes losslessly.
+#[wasm_bindgen]
+pub fn encode_image(rgba: Uint8Array, width: u32, height: u32, format: String, quality: u8) -> Result<Uint8Array, JsValue> {
+    let pixels = rgba.to_vec();
+    if pixels.len() != (width * height * 4) as usize {
+        return Err(JsValue::from_str("rgba buffer does not match width*height*4"));
+    }
+
+    let mut encoded = Vec::new();
+    match format.to_lowercase().as_str() {
+        "png" => {
+            let encoder = image::codecs::png::PngEncoder::new_with_quality(
+                &mut encoded,
+                image::codecs::png::CompressionType::Default,
+                image::codecs::png::FilterType::Adaptive,
+            );
+            encoder
+                .write_image(&pixels, width, height, image::ColorType::Rgba8)
+                .map_err(|e| JsValue::from_str(&format!("PNG encode failed: {e}")))?;
+        }
+        "jpeg" | "jpg" => {
+            // JPEG has no alpha channel; flatten onto the existing RGB.
+            let rgb: Vec<u8> = pixels.chunks_exact(4).flat_map(|p| [p[0], p[1], p[2]]).collect();
+            let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut encoded, quality.clamp(1, 100));
+            encoder
+                .encode(&rgb, width, height, image::ColorType::Rgb8)
+                .map_err(|e| JsValue::from_str(&format!("JPEG encode failed: {e}")))?;
+        }
+        other => return Err(JsValue::from_str(&format!("unsupported image format: {other}"))),
+    }
+
+    Ok(Uint8Array::from(encoded.as_slice()))
+}
+
 fn get_window() -> Result<Window, JsValue> {
     web_sys::window().ok_or_else(|| "no global `window` exists".into())
 }
+
+async fn fetch_bytes(url: &str) -> Result<Vec<u8>, JsValue> {
+    let window = get_window()?;
+
+    let opts = RequestInit::new();
+    opts.set_method("GET");
+    opts.set_mode(RequestMode::Cors);
+
+    let request = Request::new_with_str_and_init(url, &opts)?;
+    let resp_value = JsFuture::from(window.fetch_with_request(&request)).await?;
+    let resp: Response = resp_value.dyn_into()?;
+
+    if !resp.ok() {
+        return Err(JsValue::from_str(&format!("Failed to download model: HTTP {}", resp.status())));
+    }
+
+    let array_buffer = JsFuture::from(resp.array_buffer()?).await?;
+    let uint8_array = Uint8Array::new(&array_buffer);
+    let mut bytes = vec![0u8; uint8_array.length() as usize];
+    uint8_array.copy_to(&mut bytes);
+
+    Ok(bytes)
+}